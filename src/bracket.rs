@@ -0,0 +1,214 @@
+use ropey::Rope;
+use std::ops::Range;
+
+const BRACKET_PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+pub fn find_matching_bracket(contents: &Rope, index: usize) -> Option<usize> {
+  find_matching_bracket_within(contents, index, 0..contents.len_lines())
+}
+
+// like find_matching_bracket, but only looks at the given lines, so that
+// rendering does not scan the whole file for an unmatched bracket
+pub fn find_matching_bracket_within(
+  contents: &Rope,
+  index: usize,
+  lines: Range<usize>,
+) -> Option<usize> {
+  let ch = contents.get_char(index)?;
+  let pair = BRACKET_PAIRS
+    .into_iter()
+    .find(|&(open, close)| ch == open || ch == close)?;
+  find_matching_pair_within(contents, index, pair, lines)
+}
+
+// matches any pair of characters, for callers that accept more pairs than
+// the ones highlighted while editing
+pub fn find_matching_pair(contents: &Rope, index: usize, pair: (char, char)) -> Option<usize> {
+  find_matching_pair_within(contents, index, pair, 0..contents.len_lines())
+}
+
+fn find_matching_pair_within(
+  contents: &Rope,
+  index: usize,
+  (open, close): (char, char),
+  lines: Range<usize>,
+) -> Option<usize> {
+  let ch = contents.get_char(index)?;
+  if ch != open && ch != close {
+    return None;
+  }
+  let forward = ch == open;
+  let cursor_line = contents.char_to_line(index);
+  if !lines.contains(&cursor_line) {
+    return None;
+  }
+  let cursor_col = index - contents.line_to_char(cursor_line);
+  let mut chars = vec![];
+  let mut mask = vec![];
+  load_line(contents, cursor_line, &mut chars);
+  string_mask(&chars, &mut mask);
+  // brackets inside strings are skipped, unless the search starts from one
+  let skip_strings = !mask[cursor_col];
+  let mut search = Search {
+    // depth goes up on the bracket under the cursor and down on its match
+    up: if forward { open } else { close },
+    down: if forward { close } else { open },
+    depth: 0,
+  };
+  let last_line = lines.end.min(contents.len_lines());
+  let mut line = cursor_line;
+  loop {
+    if line != cursor_line {
+      load_line(contents, line, &mut chars);
+      if skip_strings {
+        string_mask(&chars, &mut mask);
+      }
+    }
+    if !skip_strings {
+      mask.clear();
+      mask.resize(chars.len(), false);
+    }
+    let col = match (forward, line == cursor_line) {
+      (true, true) => search.scan(&chars, &mask, cursor_col..chars.len()),
+      (true, false) => search.scan(&chars, &mask, 0..chars.len()),
+      (false, true) => search.scan(&chars, &mask, (0..=cursor_col).rev()),
+      (false, false) => search.scan(&chars, &mask, (0..chars.len()).rev()),
+    };
+    if let Some(col) = col {
+      return Some(contents.line_to_char(line) + col);
+    }
+    if forward {
+      line += 1;
+      if line >= last_line {
+        return None;
+      }
+    } else {
+      if line <= lines.start {
+        return None;
+      }
+      line -= 1;
+    }
+  }
+}
+
+struct Search {
+  up: char,
+  down: char,
+  depth: usize,
+}
+
+impl Search {
+  fn scan(
+    &mut self,
+    chars: &[char],
+    mask: &[bool],
+    cols: impl Iterator<Item = usize>,
+  ) -> Option<usize> {
+    for col in cols {
+      if mask[col] {
+        continue;
+      }
+      if chars[col] == self.up {
+        self.depth += 1;
+      } else if chars[col] == self.down {
+        self.depth -= 1;
+        if self.depth == 0 {
+          return Some(col);
+        }
+      }
+    }
+    None
+  }
+}
+
+fn load_line(contents: &Rope, line: usize, chars: &mut Vec<char>) {
+  chars.clear();
+  chars.extend(contents.line(line).chars());
+}
+
+// marks the characters of a line that belong to a string or character
+// literal, quotes included; strings spanning several lines are not detected
+fn string_mask(chars: &[char], mask: &mut Vec<bool>) {
+  mask.clear();
+  mask.resize(chars.len(), false);
+  let mut i = 0;
+  while i < chars.len() {
+    match chars[i] {
+      '"' => {
+        let start = i;
+        i += 1;
+        while i < chars.len() && chars[i] != '"' {
+          if chars[i] == '\\' {
+            i += 1;
+          }
+          i += 1;
+        }
+        let end = i.min(chars.len() - 1);
+        mask[start..=end].fill(true);
+      }
+      '\'' => {
+        let literal_len = match chars.get(i + 1) {
+          Some('\\') => 4,
+          Some(_) => 3,
+          None => 0,
+        };
+        if literal_len > 0 && chars.get(i + literal_len - 1) == Some(&'\'') {
+          mask[i..i + literal_len].fill(true);
+          i += literal_len - 1;
+        }
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn matches_nested_pairs() {
+    let c = Rope::from_str("a(b[c]{d(e)})f");
+    assert_eq!(find_matching_bracket(&c, 1), Some(12));
+    assert_eq!(find_matching_bracket(&c, 12), Some(1));
+    assert_eq!(find_matching_bracket(&c, 3), Some(5));
+    assert_eq!(find_matching_bracket(&c, 8), Some(10));
+    assert_eq!(find_matching_bracket(&c, 0), None);
+  }
+
+  #[test]
+  fn unbalanced_pairs() {
+    let c = Rope::from_str("((a)");
+    assert_eq!(find_matching_bracket(&c, 0), None);
+    assert_eq!(find_matching_bracket(&c, 1), Some(3));
+    assert_eq!(find_matching_bracket(&c, 4), None);
+  }
+
+  #[test]
+  fn skips_brackets_in_strings() {
+    let c = Rope::from_str("f(\"(\", ')', b\"\\\")\")");
+    assert_eq!(find_matching_bracket(&c, 1), Some(18));
+    assert_eq!(find_matching_bracket(&c, 18), Some(1));
+  }
+
+  #[test]
+  fn matches_given_pairs() {
+    let c = Rope::from_str("<a <b> \"<\">");
+    assert_eq!(find_matching_bracket(&c, 0), None);
+    assert_eq!(find_matching_pair(&c, 0, ('<', '>')), Some(10));
+    assert_eq!(find_matching_pair(&c, 5, ('<', '>')), Some(3));
+    assert_eq!(find_matching_pair(&c, 1, ('<', '>')), None);
+  }
+
+  #[test]
+  fn matches_only_within_lines() {
+    let c = Rope::from_str("(\n[\n]\n)\n");
+    assert_eq!(find_matching_bracket_within(&c, 0, 0..4), Some(6));
+    assert_eq!(find_matching_bracket_within(&c, 0, 0..3), None);
+    assert_eq!(find_matching_bracket_within(&c, 2, 1..3), Some(4));
+    assert_eq!(find_matching_bracket_within(&c, 6, 1..4), None);
+    assert_eq!(find_matching_bracket_within(&c, 4, 1..3), Some(2));
+    assert_eq!(find_matching_bracket_within(&c, 0, 1..4), None);
+  }
+}
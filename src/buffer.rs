@@ -2,6 +2,9 @@ use crate::*;
 use ropey::Rope;
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter, ErrorKind, Result};
+use std::ops::Range;
+
+const DEFAULT_INDENT_UNIT: &str = "  ";
 
 pub struct Buffer {
   pub filename: Option<String>,
@@ -9,6 +12,11 @@ pub struct Buffer {
   pub selections: Vec<Selection>,
   pub primary_selection: usize,
   pub history: History,
+  pub indent_unit: String,
+  edits: Vec<Change>,
+  // whether new edits were made since the last call to take_recorded, undo
+  // and redo only move through the history and do not count
+  recorded: bool,
 }
 
 impl Buffer {
@@ -19,6 +27,9 @@ impl Buffer {
       selections: vec![Selection::new_at_end(0, 0)],
       primary_selection: 0,
       history: Default::default(),
+      indent_unit: DEFAULT_INDENT_UNIT.to_string(),
+      edits: Vec::new(),
+      recorded: false,
     }
   }
 
@@ -38,6 +49,9 @@ impl Buffer {
       selections: vec![Selection::new_at_end(0, 0)],
       primary_selection: 0,
       history: Default::default(),
+      indent_unit: DEFAULT_INDENT_UNIT.to_string(),
+      edits: Vec::new(),
+      recorded: false,
     })
   }
 
@@ -74,6 +88,20 @@ impl Buffer {
     self.cleanup_overlaps();
   }
 
+  pub fn set_selections_and_primary(&mut self, selections: Vec<Selection>, primary_selection: usize) {
+    let Some(primary) = selections.get(primary_selection).copied() else {
+      return;
+    };
+    self.selections = selections;
+    self.cleanup_overlaps();
+    let cursor = primary.cursor();
+    self.primary_selection = self
+      .selections
+      .iter()
+      .position(|s| s.start() <= cursor && cursor <= s.end())
+      .unwrap_or(self.selections.len().saturating_sub(1));
+  }
+
   pub fn apply_operations(&mut self, ops: &[Op]) {
     for op in ops.iter() {
       for i in 0..self.selections.len() {
@@ -88,12 +116,203 @@ impl Buffer {
             .expect("should be able to retrieve selection at index less than length when adjusting selections after applying operation");
           next_selection.adjust(&self.contents, change.as_ref());
         }
-        change.map(|c| self.history.record(c));
+        if let Some(change) = change {
+          self.record(change);
+        }
       }
     }
     self.cleanup_overlaps();
   }
 
+  pub fn selection_contents(&self) -> Vec<String> {
+    let len = self.contents.len_chars();
+    self
+      .selections
+      .iter()
+      .map(|selection| {
+        if selection.start() >= len {
+          String::new()
+        } else {
+          selection.slice(&self.contents).to_string()
+        }
+      })
+      .collect()
+  }
+
+  // selections are kept sorted, so only the last one can sit at the end of
+  // the contents where it holds no text; commands moving text between
+  // selections leave it out so that nothing ends up after the final line
+  pub fn text_selection_count(&self) -> usize {
+    let len = self.contents.len_chars();
+    self
+      .selections
+      .iter()
+      .take_while(|selection| selection.start() < len)
+      .count()
+  }
+
+  pub fn replace_selections(&mut self, replacements: &[String]) {
+    let count = self.selections.len();
+    for (i, replacement) in replacements.iter().enumerate().take(count) {
+      let selection = self.selections.get_mut(i).expect(
+        "should be able to retrieve selection at index less than length when replacing",
+      );
+      let side = selection.side();
+      let begin = selection.start();
+      let change_a = selection.apply_operation(&mut self.contents, Op::RemoveAll);
+      let change_b = selection.apply_operation(&mut self.contents, Op::InsertStr(replacement));
+      // cover the inserted text instead of leaving the cursor after it
+      let end = begin + replacement.chars().count().saturating_sub(1);
+      *selection = match side {
+        Side::Start => Selection::new_at_start(begin, end),
+        Side::End => Selection::new_at_end(begin, end),
+      };
+      for j in i + 1..self.selections.len() {
+        let next_selection = self
+          .selections
+          .get_mut(j)
+          .expect("should be able to retrieve selection at index less than length when adjusting selections after replacing");
+        next_selection.adjust(&self.contents, change_a.as_ref());
+        next_selection.adjust(&self.contents, change_b.as_ref());
+      }
+      if let Some(change) = change_a {
+        self.record(change);
+      }
+      if let Some(change) = change_b {
+        self.record(change);
+      }
+    }
+    self.commit();
+  }
+
+  // ends an edit command: its changes become a single undo step and
+  // selections pushed onto each other by the edits are merged again
+  pub fn commit(&mut self) {
+    self.history.commit();
+    let selections = self.selections.clone();
+    self.set_selections_and_primary(selections, self.primary_selection);
+  }
+
+  pub fn insert(&mut self, index: usize, value: &str) {
+    if value.is_empty() {
+      return;
+    }
+    let change = Change::Addition(index, value.into());
+    self.apply_change(change);
+  }
+
+  pub fn prepend(&mut self, index: usize, value: &str) {
+    // like insert, but selections starting at the index grow to cover the
+    // inserted text instead of being pushed past it
+    let pinned = self
+      .selections
+      .iter()
+      .map(|s| s.start() == index && s.end() > index)
+      .collect::<Vec<_>>();
+    self.insert(index, value);
+    for (selection, pinned) in self.selections.iter_mut().zip(pinned) {
+      if pinned {
+        *selection = match selection.side() {
+          Side::Start => Selection::new_at_start(index, selection.end()),
+          Side::End => Selection::new_at_end(index, selection.end()),
+        };
+      }
+    }
+  }
+
+  pub fn append(&mut self, index: usize, value: &str) {
+    // like insert, but selections ending right before the index grow to
+    // cover the inserted text
+    let grown = self
+      .selections
+      .iter()
+      .map(|s| s.end() + 1 == index && s.start() < s.end())
+      .collect::<Vec<_>>();
+    let len = value.chars().count();
+    self.insert(index, value);
+    for (selection, grown) in self.selections.iter_mut().zip(grown) {
+      if grown {
+        *selection = match selection.side() {
+          Side::Start => Selection::new_at_start(selection.start(), selection.end() + len),
+          Side::End => Selection::new_at_end(selection.start(), selection.end() + len),
+        };
+      }
+    }
+  }
+
+  pub fn remove(&mut self, range: Range<usize>) {
+    if range.is_empty() {
+      return;
+    }
+    let content = self.contents.slice(range.clone()).into();
+    let change = Change::Removal(range.start, content);
+    self.apply_change(change);
+  }
+
+  pub fn replace(&mut self, range: Range<usize>, value: &str) {
+    let removed = range.len();
+    let added = value.chars().count();
+    let content = self.contents.slice(range.clone()).into();
+    let removal = Change::Removal(range.start, content);
+    let addition = Change::Addition(range.start, value.into());
+    removal.apply(&mut self.contents);
+    addition.apply(&mut self.contents);
+    // anchors inside the replaced range are pinned to the replacement instead
+    // of being pushed past it
+    let new_end = range.start + added.saturating_sub(1);
+    let map = |index: usize, fallback: usize| {
+      if index < range.start {
+        index
+      } else if index < range.end {
+        fallback
+      } else {
+        index + added - removed
+      }
+    };
+    for selection in self.selections.iter_mut() {
+      let start = map(selection.start(), range.start);
+      let end = map(selection.end(), new_end).max(start);
+      *selection = match selection.side() {
+        Side::Start => Selection::new_at_start(start, end),
+        Side::End => Selection::new_at_end(start, end),
+      };
+    }
+    if removed > 0 {
+      self.record(removal);
+    }
+    if added > 0 {
+      self.record(addition);
+    }
+  }
+
+  fn apply_change(&mut self, change: Change) {
+    change.apply(&mut self.contents);
+    for selection in self.selections.iter_mut() {
+      selection.adjust(&self.contents, Some(&change));
+    }
+    self.record(change);
+  }
+
+  pub fn apply_changes(&mut self, changes: &Changes) {
+    let selections = changes.apply(&mut self.contents);
+    self.edits.extend(changes.iter().cloned());
+    self.set_selections(selections);
+  }
+
+  pub fn record(&mut self, change: Change) {
+    self.recorded = true;
+    self.edits.push(change.clone());
+    self.history.record(change);
+  }
+
+  pub fn take_edits(&mut self) -> Vec<Change> {
+    std::mem::take(&mut self.edits)
+  }
+
+  pub fn take_recorded(&mut self) -> bool {
+    std::mem::replace(&mut self.recorded, false)
+  }
+
   pub fn cleanup_overlaps(&mut self) {
     merge_overlapping_selections(&mut self.selections);
     if self.primary_selection >= self.selections.len() {
@@ -101,3 +320,38 @@ impl Buffer {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::testing::*;
+
+  #[test]
+  fn commit_merges_selections_pushed_together() {
+    let mut b = buffer("ab\n", selections(&[(0, 0), (1, 1)]));
+    b.primary_selection = 1;
+    b.remove(0..1);
+    assert_eq!(b.selections.len(), 2);
+    b.commit();
+    assert_eq!(b.contents.to_string(), "b\n");
+    assert_eq!(b.selections.len(), 1);
+    assert_eq!(b.primary_selection, 0);
+  }
+
+  #[test]
+  fn undo_and_redo_are_not_recorded() {
+    let mut b = buffer("ab\n", selections(&[(0, 0)]));
+    b.insert(0, "x");
+    b.commit();
+    assert!(b.take_recorded());
+    assert!(!b.take_recorded());
+    let changes = b
+      .history
+      .backward()
+      .cloned()
+      .expect("history should hold the insert");
+    b.apply_changes(&changes);
+    assert_eq!(b.contents.to_string(), "ab\n");
+    assert_eq!(b.take_edits().len(), 2);
+    assert!(!b.take_recorded());
+  }
+}
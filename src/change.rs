@@ -1,7 +1,7 @@
 use crate::*;
 use ropey::Rope;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Change {
   Addition(usize, Rope),
   Removal(usize, Rope),
@@ -30,7 +30,7 @@ impl Change {
   }
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Changes(Vec<Change>);
 
 impl Changes {
@@ -52,6 +52,10 @@ impl Changes {
     selections
   }
 
+  pub fn iter(&self) -> impl Iterator<Item = &Change> {
+    self.0.iter()
+  }
+
   pub fn push(&mut self, change: Change) -> &Change {
     self.0.push(change);
     self
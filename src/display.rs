@@ -0,0 +1,70 @@
+use ropey::Rope;
+
+// every char takes exactly one cell, which is how `Ui::render` draws lines and
+// how `Window` maps screen positions back to buffer indices, so columns
+// computed here always match what is on screen
+
+pub fn line_content_end(contents: &Rope, line: usize) -> usize {
+  let line_begin = contents.line_to_char(line);
+  let line_slice = contents.line(line);
+  let len = line_slice.len_chars();
+  let mut end = line_begin + len;
+  // a "\r\n" ending is one line break, the same as in join and break lines
+  if len >= 1 && line_slice.char(len - 1) == '\n' {
+    end -= 1;
+    if len >= 2 && line_slice.char(len - 2) == '\r' {
+      end -= 1;
+    }
+  }
+  end
+}
+
+pub fn display_column(contents: &Rope, index: usize) -> usize {
+  let index = index.min(contents.len_chars());
+  index - contents.line_to_char(contents.char_to_line(index))
+}
+
+pub fn line_display_width(contents: &Rope, line: usize) -> usize {
+  line_content_end(contents, line) - contents.line_to_char(line)
+}
+
+pub fn display_range_to_chars(
+  contents: &Rope,
+  line: usize,
+  left: usize,
+  right: usize,
+) -> Option<(usize, usize)> {
+  let line_begin = contents.line_to_char(line);
+  let line_end = line_content_end(contents, line);
+  let start = line_begin + left;
+  if start >= line_end {
+    return None;
+  }
+  Some((start, (line_begin + right).min(line_end - 1)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn columns_match_rendered_cells() {
+    let c = Rope::from_str("a\tb\n漢字x\n");
+    assert_eq!(display_column(&c, 1), 1);
+    assert_eq!(display_column(&c, 2), 2);
+    assert_eq!(line_display_width(&c, 0), 3);
+    assert_eq!(display_column(&c, 6), 2);
+    assert_eq!(display_range_to_chars(&c, 0, 1, 1), Some((1, 1)));
+    assert_eq!(display_range_to_chars(&c, 1, 1, 5), Some((5, 6)));
+    assert_eq!(display_range_to_chars(&c, 1, 3, 4), None);
+  }
+
+  #[test]
+  fn crlf_is_not_content() {
+    let c = Rope::from_str("ab\r\nc\r\n");
+    assert_eq!(line_content_end(&c, 0), 2);
+    assert_eq!(line_display_width(&c, 1), 1);
+    assert_eq!(display_range_to_chars(&c, 0, 1, 3), Some((1, 1)));
+    assert_eq!(display_range_to_chars(&c, 1, 1, 1), None);
+  }
+}
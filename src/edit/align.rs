@@ -0,0 +1,92 @@
+use crate::*;
+use std::collections::BTreeMap;
+
+pub fn align(buffer: &mut Buffer, side: Side) {
+  // the nth selection of every line forms a column, so align the columns one
+  // at a time from left to right as each pass shifts the ones after it
+  let mut column = 0;
+  loop {
+    let mut lines: BTreeMap<usize, Vec<Selection>> = BTreeMap::new();
+    for selection in buffer.selections.iter() {
+      let line = selection.cursor_line(&buffer.contents);
+      lines.entry(line).or_default().push(*selection);
+    }
+    let anchors = lines
+      .values()
+      .filter_map(|selections| selections.get(column))
+      .map(|selection| {
+        let anchor = match side {
+          Side::Start => selection.start(),
+          Side::End => selection.cursor(),
+        };
+        (selection.start(), display_column(&buffer.contents, anchor))
+      })
+      .collect::<Vec<_>>();
+    if anchors.is_empty() {
+      break;
+    }
+    let target = anchors
+      .iter()
+      .map(|&(_, col)| col)
+      .max()
+      .expect("anchors should not be empty when aligning");
+    for &(start, col) in anchors.iter().rev() {
+      buffer.insert(start, &" ".repeat(target - col));
+    }
+    column += 1;
+  }
+  buffer.commit();
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn align_cursors() {
+    let mut b = buffer(
+      "a=1\nbcd=2\n\tx=3\n",
+      vec![
+        Selection::new_at_end(1, 1),
+        Selection::new_at_end(7, 7),
+        Selection::new_at_end(12, 12),
+      ],
+    );
+    align(&mut b, Side::End);
+    assert_eq!(b.contents.to_string(), "a  =1\nbcd=2\n\tx =3\n");
+    let cursors = b.selections.iter().map(|s| s.cursor()).collect::<Vec<_>>();
+    assert_eq!(cursors, vec![3, 9, 15]);
+  }
+
+  #[test]
+  fn align_columns_left_to_right() {
+    let mut b = buffer(
+      "a,b,c\naaa,b,c\n",
+      vec![
+        Selection::new_at_end(1, 1),
+        Selection::new_at_end(3, 3),
+        Selection::new_at_end(9, 9),
+        Selection::new_at_end(11, 11),
+      ],
+    );
+    align(&mut b, Side::End);
+    assert_eq!(b.contents.to_string(), "a  ,b,c\naaa,b,c\n");
+  }
+
+  #[test]
+  fn align_starts() {
+    let mut b = buffer(
+      "x foo\nxyz bar\n",
+      vec![Selection::new_at_end(2, 4), Selection::new_at_end(10, 12)],
+    );
+    align(&mut b, Side::Start);
+    assert_eq!(b.contents.to_string(), "x   foo\nxyz bar\n");
+    let ranges = b
+      .selections
+      .iter()
+      .map(|s| (s.start(), s.end()))
+      .collect::<Vec<_>>();
+    assert_eq!(ranges, vec![(4, 6), (12, 14)]);
+  }
+}
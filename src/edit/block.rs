@@ -0,0 +1,82 @@
+use crate::*;
+
+pub fn block_selections(buffer: &mut Buffer, pad: bool) -> Result<(), String> {
+  let primary = *buffer.primary_selection();
+  let contents = &buffer.contents;
+  let start = primary.start().min(contents.len_chars());
+  let end = primary.end().min(contents.len_chars());
+  let start_line = contents.char_to_line(start);
+  let end_line = contents.char_to_line(end);
+  let cursor_line = primary.cursor_line(contents).min(end_line);
+  let start_column = display_column(contents, start);
+  let end_column = display_column(contents, end);
+  let left = start_column.min(end_column);
+  let right = start_column.max(end_column);
+  if pad {
+    for line in (start_line..=end_line).rev() {
+      let width = line_display_width(&buffer.contents, line);
+      if width <= right {
+        let line_end = line_content_end(&buffer.contents, line);
+        buffer.insert(line_end, &" ".repeat(right + 1 - width));
+      }
+    }
+    buffer.commit();
+  }
+  let mut selections = vec![];
+  let mut primary_selection = None;
+  for line in start_line..=end_line {
+    let Some((start, end)) = display_range_to_chars(&buffer.contents, line, left, right) else {
+      continue;
+    };
+    if line <= cursor_line {
+      primary_selection = Some(selections.len());
+    }
+    selections.push(match primary.side() {
+      Side::Start => Selection::new_at_start(start, end),
+      Side::End => Selection::new_at_end(start, end),
+    });
+  }
+  if selections.is_empty() {
+    return Err("no lines reach the block columns".into());
+  }
+  buffer.set_selections_and_primary(selections, primary_selection.unwrap_or(0));
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn columns_follow_rendered_cells() {
+    // the tab is drawn in a single cell, so it belongs to column 1
+    let mut b = buffer("a\tbc\nxyzw\n", vec![Selection::new_at_end(1, 7)]);
+    block_selections(&mut b, false).unwrap();
+    assert_eq!(ranges(&b.selections), vec![(1, 2), (6, 7)]);
+  }
+
+  #[test]
+  fn short_lines_are_skipped_or_padded() {
+    let text = "abcd\na\nabcd\n";
+    let mut b = buffer(text, vec![Selection::new_at_end(2, 10)]);
+    block_selections(&mut b, false).unwrap();
+    assert_eq!(ranges(&b.selections), vec![(2, 3), (9, 10)]);
+    let mut b = buffer(text, vec![Selection::new_at_end(2, 10)]);
+    block_selections(&mut b, true).unwrap();
+    assert_eq!(b.contents.to_string(), "abcd\na   \nabcd\n");
+    assert_eq!(ranges(&b.selections), vec![(2, 3), (7, 8), (12, 13)]);
+  }
+
+  #[test]
+  fn crlf_line_endings_are_kept_last() {
+    let text = "abcd\r\na\r\nabcd\r\n";
+    let mut b = buffer(text, selections(&[(2, 12)]));
+    block_selections(&mut b, false).unwrap();
+    assert_eq!(ranges(&b.selections), vec![(2, 3), (11, 12)]);
+    let mut b = buffer(text, selections(&[(2, 12)]));
+    block_selections(&mut b, true).unwrap();
+    assert_eq!(b.contents.to_string(), "abcd\r\na   \r\nabcd\r\n");
+    assert_eq!(ranges(&b.selections), vec![(2, 3), (8, 9), (14, 15)]);
+  }
+}
@@ -0,0 +1,161 @@
+use crate::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Case {
+  Upper,
+  Lower,
+  Swap,
+  Title,
+  Snake,
+  Camel,
+  Pascal,
+  Kebab,
+}
+
+impl Case {
+  pub fn convert(&self, value: &str) -> String {
+    match self {
+      Case::Upper => value.to_uppercase(),
+      Case::Lower => value.to_lowercase(),
+      Case::Swap => value.chars().flat_map(swap_case).collect(),
+      Case::Title => title_case(value),
+      Case::Snake => join_words(value, "_", |_, word| word.to_lowercase()),
+      Case::Kebab => join_words(value, "-", |_, word| word.to_lowercase()),
+      Case::Camel => join_words(value, "", |i, word| match i {
+        0 => word.to_lowercase(),
+        _ => capitalize(word),
+      }),
+      Case::Pascal => join_words(value, "", |_, word| capitalize(word)),
+    }
+  }
+}
+
+pub fn convert_case(buffer: &mut Buffer, case: Case) {
+  let contents = buffer
+    .selection_contents()
+    .iter()
+    .map(|content| case.convert(content))
+    .collect::<Vec<_>>();
+  buffer.replace_selections(&contents);
+}
+
+fn swap_case(ch: char) -> Vec<char> {
+  if ch.is_uppercase() {
+    ch.to_lowercase().collect()
+  } else if ch.is_lowercase() {
+    ch.to_uppercase().collect()
+  } else {
+    vec![ch]
+  }
+}
+
+fn capitalize(word: &str) -> String {
+  let mut chars = word.chars();
+  match chars.next() {
+    Some(first) => first
+      .to_uppercase()
+      .chain(chars.as_str().to_lowercase().chars())
+      .collect(),
+    None => String::new(),
+  }
+}
+
+fn title_case(value: &str) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut at_word_start = true;
+  for ch in value.chars() {
+    if ch.is_alphanumeric() {
+      if at_word_start {
+        result.extend(ch.to_uppercase());
+      } else {
+        result.extend(ch.to_lowercase());
+      }
+      at_word_start = false;
+    } else {
+      result.push(ch);
+      at_word_start = ch.is_whitespace() || ch == '_' || ch == '-';
+    }
+  }
+  result
+}
+
+fn join_words(value: &str, separator: &str, format: impl Fn(usize, &str) -> String) -> String {
+  // keep surrounding whitespace so that converting whole lines keeps them intact
+  let trimmed = value.trim();
+  let Some(offset) = value.find(trimmed) else {
+    return value.to_string();
+  };
+  let words = split_words(trimmed)
+    .iter()
+    .enumerate()
+    .map(|(i, word)| format(i, word))
+    .collect::<Vec<_>>();
+  format!(
+    "{}{}{}",
+    &value[..offset],
+    words.join(separator),
+    &value[offset + trimmed.len()..]
+  )
+}
+
+fn split_words(value: &str) -> Vec<String> {
+  let chars = value.chars().collect::<Vec<_>>();
+  let mut words = vec![];
+  let mut word = String::new();
+  for (i, &ch) in chars.iter().enumerate() {
+    if !ch.is_alphanumeric() {
+      if !word.is_empty() {
+        words.push(std::mem::take(&mut word));
+      }
+      continue;
+    }
+    // split before an uppercase letter that follows a lowercase one, and
+    // before the last uppercase letter of an acronym followed by lowercase
+    let prev = i.checked_sub(1).map(|i| chars[i]);
+    let next = chars.get(i + 1).copied();
+    let boundary = ch.is_uppercase()
+      && match prev {
+        Some(prev) if prev.is_lowercase() || prev.is_numeric() => true,
+        Some(prev) if prev.is_uppercase() => next.is_some_and(|next| next.is_lowercase()),
+        _ => false,
+      };
+    if boundary && !word.is_empty() {
+      words.push(std::mem::take(&mut word));
+    }
+    word.push(ch);
+  }
+  if !word.is_empty() {
+    words.push(word);
+  }
+  words
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn simple_cases() {
+    assert_eq!(Case::Upper.convert("straße"), "STRASSE");
+    assert_eq!(Case::Lower.convert("ÀÉÎ"), "àéî");
+    assert_eq!(Case::Swap.convert("Hello Ωmega"), "hELLO ωMEGA");
+    assert_eq!(
+      Case::Title.convert("the quick-brown fox\n"),
+      "The Quick-Brown Fox\n"
+    );
+  }
+
+  #[test]
+  fn identifier_cases() {
+    assert_eq!(
+      Case::Snake.convert("parseHTTPResponse2Body"),
+      "parse_http_response2_body"
+    );
+    assert_eq!(
+      Case::Kebab.convert("  Some value_here\n"),
+      "  some-value-here\n"
+    );
+    assert_eq!(Case::Camel.convert("user_id_value"), "userIdValue");
+    assert_eq!(Case::Pascal.convert("über-große zahl"), "ÜberGroßeZahl");
+  }
+}
@@ -0,0 +1,229 @@
+use crate::*;
+use ropey::Rope;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+enum Edit {
+  Prepend(usize, String),
+  Append(usize, String),
+  Remove(Range<usize>),
+}
+
+impl Edit {
+  fn index(&self) -> usize {
+    match self {
+      Self::Prepend(index, _) | Self::Append(index, _) => *index,
+      Self::Remove(range) => range.start,
+    }
+  }
+}
+
+pub fn toggle_comments(buffer: &mut Buffer) -> Result<(), String> {
+  let tokens = buffer
+    .filename
+    .as_deref()
+    .and_then(comment_tokens)
+    .ok_or_else(|| "error: no comment syntax known for this file".to_string())?;
+  let contents = &buffer.contents;
+  let mut line_groups = Vec::new();
+  let mut blocks = Vec::new();
+  let mut seen = BTreeSet::new();
+  for selection in buffer.selections.iter() {
+    let start = selection.start().min(contents.len_chars());
+    let end = selection.end().min(contents.len_chars());
+    let whole_lines = start == end || covers_whole_lines(contents, start, end);
+    match (whole_lines, tokens.line, tokens.block) {
+      (true, Some(_), _) | (false, Some(_), None) => {
+        let first = contents.char_to_line(start);
+        let last = contents.char_to_line(end);
+        let lines = (first..=last)
+          .filter(|&line| !is_blank(contents, line) && seen.insert(line))
+          .collect::<Vec<_>>();
+        line_groups.push(lines);
+      }
+      (true, None, Some(_)) => {
+        let first = contents.line_to_char(contents.char_to_line(start));
+        let last = contents.char_to_line(end);
+        let last = contents.line_to_char(last) + line_content_len(contents, last);
+        if let Some(range) = trim_range(contents, first, last) {
+          blocks.push(range);
+        }
+      }
+      (false, _, Some(_)) => blocks.push(start..end + 1),
+      (_, None, None) => unreachable!("should know at least one comment token"),
+    }
+  }
+  let mut edits = Vec::new();
+  if let Some(token) = tokens.line {
+    line_edits(contents, token, &line_groups, &mut edits);
+  }
+  if let Some((open, close)) = tokens.block {
+    for range in blocks {
+      block_edits(contents, open, close, range, &mut edits);
+    }
+  }
+  edits.sort_by_key(|edit| std::cmp::Reverse(edit.index()));
+  for edit in edits {
+    match edit {
+      Edit::Prepend(index, value) => buffer.prepend(index, &value),
+      Edit::Append(index, value) => buffer.append(index, &value),
+      Edit::Remove(range) => buffer.remove(range),
+    }
+  }
+  buffer.commit();
+  Ok(())
+}
+
+fn line_edits(contents: &Rope, token: &str, groups: &[Vec<usize>], edits: &mut Vec<Edit>) {
+  let token_len = token.chars().count();
+  let commented = |line: usize| {
+    let begin = contents.line_to_char(line) + indent_len(contents, line);
+    starts_with(contents, begin, token)
+  };
+  let uncomment = groups.iter().flatten().all(|&line| commented(line));
+  for lines in groups {
+    let Some(column) = lines.iter().map(|&line| indent_len(contents, line)).min() else {
+      continue;
+    };
+    for &line in lines {
+      let line_begin = contents.line_to_char(line);
+      if uncomment {
+        let begin = line_begin + indent_len(contents, line);
+        let mut end = begin + token_len;
+        if contents.get_char(end) == Some(' ') {
+          end += 1;
+        }
+        edits.push(Edit::Remove(begin..end));
+      } else {
+        edits.push(Edit::Prepend(line_begin + column, format!("{token} ")));
+      }
+    }
+  }
+}
+
+fn block_edits(
+  contents: &Rope,
+  open: &str,
+  close: &str,
+  range: Range<usize>,
+  edits: &mut Vec<Edit>,
+) {
+  let Some(range) = trim_range(contents, range.start, range.end) else {
+    return;
+  };
+  let open_len = open.chars().count();
+  let close_len = close.chars().count();
+  let commented = range.end - range.start >= open_len + close_len
+    && starts_with(contents, range.start, open)
+    && starts_with(contents, range.end - close_len, close);
+  if commented {
+    let mut close_begin = range.end - close_len;
+    if close_begin > range.start + open_len && contents.char(close_begin - 1) == ' ' {
+      close_begin -= 1;
+    }
+    let mut open_end = range.start + open_len;
+    if open_end < close_begin && contents.char(open_end) == ' ' {
+      open_end += 1;
+    }
+    edits.push(Edit::Remove(close_begin..range.end));
+    edits.push(Edit::Remove(range.start..open_end));
+  } else {
+    edits.push(Edit::Append(range.end, format!(" {close}")));
+    edits.push(Edit::Prepend(range.start, format!("{open} ")));
+  }
+}
+
+// a selection covers whole lines when only whitespace lies between its
+// edges and the line boundaries
+fn covers_whole_lines(contents: &Rope, start: usize, end: usize) -> bool {
+  let first = contents.char_to_line(start);
+  let last = contents.char_to_line(end);
+  let before = contents.line_to_char(first) + indent_len(contents, first);
+  let last_end = contents.line_to_char(last) + line_content_len(contents, last);
+  let after = contents
+    .slice(end.min(last_end)..last_end)
+    .chars()
+    .all(|ch| ch.is_whitespace());
+  start <= before && (end >= last_end || after)
+}
+
+fn trim_range(contents: &Rope, mut start: usize, mut end: usize) -> Option<Range<usize>> {
+  let end_limit = end.min(contents.len_chars());
+  end = end_limit;
+  while start < end && contents.char(start).is_whitespace() {
+    start += 1;
+  }
+  while end > start && contents.char(end - 1).is_whitespace() {
+    end -= 1;
+  }
+  (start < end).then_some(start..end)
+}
+
+fn starts_with(contents: &Rope, index: usize, token: &str) -> bool {
+  let mut chars = contents.chars_at(index.min(contents.len_chars()));
+  token.chars().all(|ch| chars.next() == Some(ch))
+}
+
+fn indent_len(contents: &Rope, line: usize) -> usize {
+  contents
+    .line(line)
+    .chars()
+    .take_while(|&ch| ch == ' ' || ch == '\t')
+    .count()
+}
+
+fn line_content_len(contents: &Rope, line: usize) -> usize {
+  let line = contents.line(line);
+  let len = line.len_chars();
+  match line.get_char(len.saturating_sub(1)) {
+    Some('\n') => len - 1,
+    _ => len,
+  }
+}
+
+fn is_blank(contents: &Rope, line: usize) -> bool {
+  contents.line(line).chars().all(|ch| ch.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  fn source(filename: &str, text: &str, selections: Vec<Selection>) -> Buffer {
+    let mut b = buffer(text, selections);
+    b.filename = Some(filename.into());
+    b
+  }
+
+  #[test]
+  fn toggle_line_comments() {
+    let text = "fn a() {\n  b();\n\n    c();\n}\n";
+    let mut b = source("a.rs", text, vec![Selection::new_at_end(9, 25)]);
+    toggle_comments(&mut b).unwrap();
+    assert_eq!(
+      b.contents.to_string(),
+      "fn a() {\n  // b();\n\n  //   c();\n}\n"
+    );
+    toggle_comments(&mut b).unwrap();
+    assert_eq!(b.contents.to_string(), text);
+  }
+
+  #[test]
+  fn mixed_lines_are_commented() {
+    let text = "# a\nb\n";
+    let mut b = source("x.py", text, vec![Selection::new_at_end(0, 5)]);
+    toggle_comments(&mut b).unwrap();
+    assert_eq!(b.contents.to_string(), "# # a\n# b\n");
+  }
+
+  #[test]
+  fn toggle_block_comment() {
+    let text = "let a = b + c;\n";
+    let mut b = source("a.rs", text, vec![Selection::new_at_end(8, 12)]);
+    toggle_comments(&mut b).unwrap();
+    assert_eq!(b.contents.to_string(), "let a = /* b + c */;\n");
+    toggle_comments(&mut b).unwrap();
+    assert_eq!(b.contents.to_string(), text);
+  }
+}
@@ -0,0 +1,110 @@
+use crate::*;
+use std::collections::BTreeSet;
+
+pub fn indent_lines(buffer: &mut Buffer) {
+  let contents = &buffer.contents;
+  let lines = selected_lines(buffer)
+    .into_iter()
+    .filter(|&line| !contents.line(line).chars().all(|ch| ch.is_whitespace()))
+    .collect::<BTreeSet<_>>();
+  // selections starting at the beginning of a line keep covering the whole
+  // line once the indent is inserted in front of them
+  let pinned = buffer
+    .selections
+    .iter()
+    .map(|selection| {
+      let start = selection.start();
+      selection.end() > start
+        && start < contents.len_chars()
+        && lines.contains(&contents.char_to_line(start))
+        && contents.line_to_char(contents.char_to_line(start)) == start
+    })
+    .collect::<Vec<_>>();
+  let unit = buffer.indent_unit.clone();
+  for &line in lines.iter().rev() {
+    let line_begin = buffer.contents.line_to_char(line);
+    buffer.insert(line_begin, &unit);
+  }
+  let unit_len = unit.chars().count();
+  for (selection, pinned) in buffer.selections.iter_mut().zip(pinned) {
+    if pinned {
+      let start = selection.start() - unit_len;
+      *selection = match selection.side() {
+        Side::Start => Selection::new_at_start(start, selection.end()),
+        Side::End => Selection::new_at_end(start, selection.end()),
+      };
+    }
+  }
+  buffer.commit();
+}
+
+pub fn dedent_lines(buffer: &mut Buffer) {
+  let lines = selected_lines(buffer);
+  let unit_len = buffer.indent_unit.chars().count();
+  for &line in lines.iter().rev() {
+    let line_begin = buffer.contents.line_to_char(line);
+    let mut width = 0;
+    let mut count = 0;
+    for ch in buffer.contents.line(line).chars() {
+      match ch {
+        ' ' if width < unit_len => width += 1,
+        '\t' if width == 0 => width = unit_len,
+        _ => break,
+      }
+      count += 1;
+    }
+    buffer.remove(line_begin..line_begin + count);
+  }
+  buffer.commit();
+}
+
+fn selected_lines(buffer: &Buffer) -> BTreeSet<usize> {
+  let contents = &buffer.contents;
+  let mut lines = BTreeSet::new();
+  for selection in buffer.selections.iter() {
+    let start = contents.char_to_line(selection.start().min(contents.len_chars()));
+    let end = contents.char_to_line(selection.end().min(contents.len_chars()));
+    lines.extend(start..=end);
+  }
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn indent_shifts_each_line_once() {
+    // both selections touch the second line, which is still indented once
+    let mut b = buffer(
+      "a\nb\n\nc\n",
+      vec![Selection::new_at_end(0, 2), Selection::new_at_end(3, 6)],
+    );
+    indent_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "  a\n  b\n\n  c\n");
+    // the first selection started at the line start and still covers it
+    assert_eq!(ranges(&b.selections), vec![(0, 6), (7, 12)]);
+  }
+
+  #[test]
+  fn dedent_shifts_each_line_once() {
+    let mut b = buffer(
+      "    a\n\t b\n\n c\n",
+      vec![Selection::new_at_end(4, 8), Selection::new_at_end(8, 13)],
+    );
+    dedent_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "  a\n b\n\nc\n");
+  }
+
+  #[test]
+  fn dedent_merges_cursors_pushed_together() {
+    let mut b = buffer(
+      "  ab\n",
+      vec![Selection::new_at_end(0, 0), Selection::new_at_end(1, 1)],
+    );
+    dedent_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "ab\n");
+    assert_eq!(ranges(&b.selections), vec![(0, 0)]);
+  }
+}
@@ -0,0 +1,188 @@
+use crate::*;
+use ropey::Rope;
+
+pub fn join_lines(buffer: &mut Buffer) {
+  let contents = &buffer.contents;
+  let mut breaks = vec![];
+  for selection in buffer.selections.iter() {
+    let start_line = contents.char_to_line(selection.start().min(contents.len_chars()));
+    let end_line = contents.char_to_line(selection.end().min(contents.len_chars()));
+    // a selection within a single line joins it with the line after
+    let end_line = end_line.max(start_line + 1);
+    for line in start_line..end_line {
+      // the empty line after a final line break is not joined, that would
+      // only remove the line break
+      let next = line + 1;
+      let is_last_empty = next + 1 == contents.len_lines() && contents.line(next).len_chars() == 0;
+      if next >= contents.len_lines() || is_last_empty {
+        break;
+      }
+      breaks.push(line_break_range(contents, line));
+    }
+  }
+  // the break before a line holding only whitespace reaches into the break
+  // after it, so touching ranges become one that ends with the last separator
+  breaks.sort_by_key(|&(start, _, _)| start);
+  let mut merged: Vec<(usize, usize, &str)> = vec![];
+  for (start, end, separator) in breaks {
+    match merged.last_mut() {
+      Some(last) if start <= last.1 => {
+        if end >= last.1 {
+          *last = (last.0, end, separator);
+        }
+      }
+      _ => merged.push((start, end, separator)),
+    }
+  }
+  for (start, end, separator) in merged.into_iter().rev() {
+    buffer.replace(start..end, separator);
+  }
+  buffer.commit();
+}
+
+pub fn break_lines(buffer: &mut Buffer, delimiter: &str, keep_indent: bool) {
+  if delimiter.is_empty() {
+    return;
+  }
+  let contents = &buffer.contents;
+  let delimiter = delimiter.chars().collect::<Vec<_>>();
+  let mut breaks = vec![];
+  for selection in buffer.selections.iter() {
+    let end = (selection.end() + 1).min(contents.len_chars());
+    if selection.start() >= end {
+      continue;
+    }
+    let chars = contents
+      .slice(selection.start()..end)
+      .chars()
+      .collect::<Vec<_>>();
+    let line = contents.char_to_line(selection.start());
+    let indent = if keep_indent {
+      indent_of(contents, line)
+    } else {
+      String::new()
+    };
+    let line_ending = line_ending_of(contents, line);
+    let mut i = 0;
+    while i + delimiter.len() <= chars.len() {
+      if chars[i..i + delimiter.len()] != delimiter[..] {
+        i += 1;
+        continue;
+      }
+      i += delimiter.len();
+      let whitespace_end = i
+        + chars[i..]
+          .iter()
+          .take_while(|ch| **ch == ' ' || **ch == '\t')
+          .count();
+      // nothing left to break off after a trailing delimiter
+      if whitespace_end < chars.len() && !matches!(chars[whitespace_end], '\r' | '\n') {
+        let range = selection.start() + i..selection.start() + whitespace_end;
+        breaks.push((range, format!("{line_ending}{indent}")));
+      }
+      i = whitespace_end;
+    }
+  }
+  breaks.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+  for (range, value) in breaks {
+    buffer.replace(range, &value);
+  }
+  buffer.commit();
+}
+
+fn line_break_range(contents: &Rope, line: usize) -> (usize, usize, &'static str) {
+  let line_begin = contents.line_to_char(line);
+  let next_begin = contents.line_to_char(line + 1);
+  let chars = contents
+    .slice(line_begin..next_begin)
+    .chars()
+    .collect::<Vec<_>>();
+  let ending_len = line_ending_of(contents, line).chars().count();
+  let trailing = chars
+    .iter()
+    .rev()
+    .skip(ending_len)
+    .take_while(|ch| **ch == ' ' || **ch == '\t')
+    .count();
+  let leading = indent_of(contents, line + 1).chars().count();
+  let start = next_begin - ending_len - trailing;
+  let end = next_begin + leading;
+  let next_line_empty = contents
+    .get_char(end)
+    .is_none_or(|ch| ch == '\r' || ch == '\n');
+  let separator = if next_line_empty { "" } else { " " };
+  (start, end, separator)
+}
+
+fn line_ending_of(contents: &Rope, line: usize) -> &'static str {
+  let line = contents.line(line);
+  let len = line.len_chars();
+  if len >= 2 && line.char(len - 2) == '\r' && line.char(len - 1) == '\n' {
+    "\r\n"
+  } else {
+    "\n"
+  }
+}
+
+fn indent_of(contents: &Rope, line: usize) -> String {
+  contents
+    .line(line)
+    .chars()
+    .take_while(|ch| *ch == ' ' || *ch == '\t')
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn join_trims_whitespace_around_breaks() {
+    let mut b = buffer("a  \n   b\n\nc\n", vec![Selection::new_at_end(0, 10)]);
+    join_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "a b c\n");
+  }
+
+  #[test]
+  fn join_merges_selections_pushed_together() {
+    let mut b = buffer(
+      "a  \n   b\n",
+      vec![Selection::new_at_end(0, 1), Selection::new_at_end(2, 5)],
+    );
+    join_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "a b\n");
+    assert_eq!(ranges(&b.selections), vec![(0, 1)]);
+  }
+
+  #[test]
+  fn join_keeps_final_line_break() {
+    let mut b = buffer("a\nb\n", vec![Selection::new_at_end(2, 2)]);
+    join_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "a\nb\n");
+  }
+
+  #[test]
+  fn join_across_whitespace_only_lines() {
+    let mut b = buffer("a\n   \nb\n", vec![Selection::new_at_end(0, 6)]);
+    join_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "a b\n");
+    let mut b = buffer("a\n  \n  \nb\n", vec![Selection::new_at_end(0, 8)]);
+    join_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "a b\n");
+  }
+
+  #[test]
+  fn join_crlf_lines() {
+    let mut b = buffer("a \r\n b\r\nc\r\n", vec![Selection::new_at_end(0, 8)]);
+    join_lines(&mut b);
+    assert_eq!(b.contents.to_string(), "a b c\r\n");
+  }
+
+  #[test]
+  fn break_after_delimiter() {
+    let mut b = buffer("  f(a, b,c)\r\n", vec![Selection::new_at_end(4, 9)]);
+    break_lines(&mut b, ",", true);
+    assert_eq!(b.contents.to_string(), "  f(a,\r\n  b,\r\n  c)\r\n");
+  }
+}
@@ -0,0 +1,23 @@
+mod align;
+mod block;
+mod case;
+mod comment;
+mod indent;
+mod lines;
+mod number;
+mod rotate;
+mod sort;
+mod surround;
+mod trim;
+
+pub use align::*;
+pub use block::*;
+pub use case::*;
+pub use comment::*;
+pub use indent::*;
+pub use lines::*;
+pub use number::*;
+pub use rotate::*;
+pub use sort::*;
+pub use surround::*;
+pub use trim::*;
@@ -0,0 +1,220 @@
+use crate::*;
+use std::cmp::Reverse;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Number {
+  start: usize,
+  end: usize,
+  value: i128,
+  radix: u32,
+  prefix: &'static str,
+  width: usize,
+  uppercase: bool,
+}
+
+impl Number {
+  fn format(&self, value: i128) -> String {
+    let digits = match self.radix {
+      16 if self.uppercase => format!("{:X}", value.unsigned_abs()),
+      16 => format!("{:x}", value.unsigned_abs()),
+      2 => format!("{:b}", value.unsigned_abs()),
+      _ => format!("{}", value.unsigned_abs()),
+    };
+    let sign = if value < 0 { "-" } else { "" };
+    format!("{sign}{}{digits:0>width$}", self.prefix, width = self.width)
+  }
+}
+
+pub fn increment_numbers(buffer: &mut Buffer, delta: i64) {
+  let edits = buffer
+    .selections
+    .iter()
+    .filter_map(|selection| {
+      let number = number_at_cursor(&buffer.contents, selection.cursor())?;
+      let value = number.format(number.value.saturating_add(delta as i128));
+      Some((number.start, number.end, value))
+    })
+    .collect::<Vec<_>>();
+  apply_edits(buffer, edits);
+}
+
+pub fn number_selections(buffer: &mut Buffer, first: i64) {
+  let len = buffer.selections.len();
+  let mut edits = Vec::with_capacity(len);
+  // number the selections starting from the primary one like copy and paste
+  for i in 0..len {
+    let index = (buffer.primary_selection + i) % len;
+    let cursor = buffer.selections[index].cursor();
+    let value = first as i128 + i as i128;
+    match number_at_cursor(&buffer.contents, cursor) {
+      Some(number) => edits.push((number.start, number.end, number.format(value))),
+      None => {
+        let cursor = cursor.min(buffer.contents.len_chars());
+        edits.push((cursor, cursor, value.to_string()));
+      }
+    }
+  }
+  apply_edits(buffer, edits);
+}
+
+fn apply_edits(buffer: &mut Buffer, mut edits: Vec<(usize, usize, String)>) {
+  // apply back to front so earlier ranges stay valid, skipping ranges that
+  // were already handled through another selection on the same number
+  edits.sort_by_key(|&(start, _, _)| Reverse(start));
+  edits.dedup_by(|a, b| a.0 == b.0);
+  for (start, end, value) in edits {
+    buffer.replace(start..end, &value);
+  }
+  buffer.commit();
+}
+
+fn number_at_cursor(contents: &ropey::Rope, cursor: usize) -> Option<Number> {
+  if cursor >= contents.len_chars() {
+    return None;
+  }
+  let line = contents.char_to_line(cursor);
+  let line_begin = contents.line_to_char(line);
+  let chars = contents.line(line).chars().collect::<Vec<_>>();
+  let number = find_number(&chars, cursor - line_begin)?;
+  Some(Number {
+    start: number.start + line_begin,
+    end: number.end + line_begin,
+    ..number
+  })
+}
+
+fn find_number(chars: &[char], col: usize) -> Option<Number> {
+  let mut i = 0;
+  while i < chars.len() {
+    let Some(number) = parse_number(chars, i) else {
+      i += 1;
+      continue;
+    };
+    if number.end > col {
+      return Some(number);
+    }
+    i = number.end;
+  }
+  None
+}
+
+fn parse_number(chars: &[char], start: usize) -> Option<Number> {
+  let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+  if at(start) == '-' {
+    let after_word = start > 0 && at(start - 1).is_alphanumeric();
+    if after_word || !at(start + 1).is_ascii_digit() {
+      return None;
+    }
+    let number = parse_unsigned(chars, start + 1)?;
+    if number.radix != 10 {
+      return None;
+    }
+    return Some(Number {
+      start,
+      value: -number.value,
+      ..number
+    });
+  }
+  if start > 0 && (at(start - 1).is_ascii_alphanumeric() || at(start - 1) == '_') {
+    return None;
+  }
+  parse_unsigned(chars, start)
+}
+
+fn parse_unsigned(chars: &[char], start: usize) -> Option<Number> {
+  let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+  if !at(start).is_ascii_digit() {
+    return None;
+  }
+  let (radix, prefix) = match (at(start), at(start + 1)) {
+    ('0', 'x' | 'X') if at(start + 2).is_ascii_hexdigit() => (16, "0x"),
+    ('0', 'b' | 'B') if matches!(at(start + 2), '0' | '1') => (2, "0b"),
+    _ => (10, ""),
+  };
+  let digits_start = start + prefix.len();
+  let digits = chars[digits_start..]
+    .iter()
+    .take_while(|ch| ch.is_digit(radix))
+    .collect::<String>();
+  let value = i128::from_str_radix(&digits, radix).ok()?;
+  let padded = radix != 10 || (digits.len() > 1 && digits.starts_with('0'));
+  Some(Number {
+    start,
+    end: digits_start + digits.len(),
+    value,
+    radix,
+    prefix,
+    width: if padded { digits.len() } else { 0 },
+    uppercase: digits.chars().any(|ch| ch.is_ascii_uppercase()),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  fn increment(line: &str, col: usize, delta: i128) -> Option<String> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let number = find_number(&chars, col)?;
+    let mut result = chars[..number.start].iter().collect::<String>();
+    result.push_str(&number.format(number.value + delta));
+    result.extend(chars[number.end..].iter());
+    Some(result)
+  }
+
+  #[test]
+  fn decimal() {
+    assert_eq!(increment("x = 9;", 0, 1), Some("x = 10;".into()));
+    assert_eq!(increment("x = 10;", 5, -1), Some("x = 9;".into()));
+    assert_eq!(increment("a1 b2", 0, 1), None);
+    assert_eq!(increment("a1 22", 0, 5), Some("a1 27".into()));
+    assert_eq!(increment("none", 0, 1), None);
+  }
+
+  #[test]
+  fn negative() {
+    assert_eq!(increment("x = -1", 0, 3), Some("x = 2".into()));
+    assert_eq!(increment("x = 1", 0, -3), Some("x = -2".into()));
+    assert_eq!(increment("x-1", 0, 1), Some("x-2".into()));
+  }
+
+  #[test]
+  fn padding() {
+    assert_eq!(increment("007", 0, 1), Some("008".into()));
+    assert_eq!(increment("099", 1, 1), Some("100".into()));
+    assert_eq!(increment("-007", 0, 10), Some("003".into()));
+  }
+
+  #[test]
+  fn hex_and_binary() {
+    assert_eq!(increment("0x0f", 0, 1), Some("0x10".into()));
+    assert_eq!(increment("0xFF", 0, 1), Some("0x100".into()));
+    assert_eq!(increment("0b0111", 0, 1), Some("0b1000".into()));
+    assert_eq!(increment("0x1F", 3, -1), Some("0x1E".into()));
+  }
+
+  #[test]
+  fn increment_numbers_in_buffer() {
+    let text = "a 9 b -1 c 5\n";
+    let cursors = selections(&[(0, 0), (5, 5), (10, 10)]);
+    let mut b = buffer(text, cursors.clone());
+    increment_numbers(&mut b, 1);
+    assert_eq!(b.contents.to_string(), "a 10 b 0 c 6\n");
+    let mut b = buffer(text, cursors);
+    increment_numbers(&mut b, -10);
+    assert_eq!(b.contents.to_string(), "a -1 b -11 c -5\n");
+    assert_eq!(b.selections.len(), 3);
+  }
+
+  #[test]
+  fn number_selections_from_primary() {
+    let mut b = buffer("x\ny\nz\n", selections(&[(0, 0), (2, 2), (4, 4)]));
+    b.primary_selection = 1;
+    number_selections(&mut b, -1);
+    assert_eq!(b.contents.to_string(), "1x\n-1y\n0z\n");
+    let mut b = buffer("9 9 9", selections(&[(0, 0), (2, 2), (4, 4)]));
+    number_selections(&mut b, 9);
+    assert_eq!(b.contents.to_string(), "9 10 11");
+  }
+}
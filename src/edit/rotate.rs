@@ -0,0 +1,62 @@
+use crate::*;
+
+pub fn rotate_contents(buffer: &mut Buffer, delta: isize) {
+  let len = buffer.text_selection_count();
+  if len < 2 {
+    return;
+  }
+  let mut contents = buffer.selection_contents();
+  let shift = delta.unsigned_abs() % len;
+  if delta > 0 {
+    contents[..len].rotate_right(shift);
+  } else {
+    contents[..len].rotate_left(shift);
+  }
+  // the primary selection follows its content to its new position
+  let primary_selection = match buffer.primary_selection {
+    primary_selection if primary_selection >= len => primary_selection,
+    primary_selection if delta > 0 => (primary_selection + shift) % len,
+    primary_selection => (primary_selection + len - shift) % len,
+  };
+  buffer.replace_selections(&contents);
+  buffer.primary_selection = primary_selection.min(buffer.selections.len().saturating_sub(1));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn rotate_forward_and_back() {
+    let mut b = buffer("a,bb,c", selections(&[(0, 0), (2, 3), (5, 5)]));
+    rotate_contents(&mut b, 1);
+    assert_eq!(b.contents.to_string(), "c,a,bb");
+    assert_eq!(b.primary_selection, 1);
+    rotate_contents(&mut b, -1);
+    assert_eq!(b.contents.to_string(), "a,bb,c");
+    assert_eq!(b.primary_selection, 0);
+  }
+
+  #[test]
+  fn rotate_wraps_counts() {
+    let mut b = buffer("a,b,c", selections(&[(0, 0), (2, 2), (4, 4)]));
+    rotate_contents(&mut b, 4);
+    assert_eq!(b.contents.to_string(), "c,a,b");
+    rotate_contents(&mut b, -3);
+    assert_eq!(b.contents.to_string(), "c,a,b");
+  }
+
+  #[test]
+  fn rotate_leaves_end_of_file_cursor_empty() {
+    let mut b = buffer("a,b\n", selections(&[(0, 0), (2, 2), (4, 4)]));
+    b.primary_selection = 2;
+    rotate_contents(&mut b, 1);
+    assert_eq!(b.contents.to_string(), "b,a\n");
+    assert_eq!(ranges(&b.selections), vec![(0, 0), (2, 2), (4, 4)]);
+    assert_eq!(b.primary_selection, 2);
+    let mut b = buffer("a\n", selections(&[(0, 0), (2, 2)]));
+    rotate_contents(&mut b, 1);
+    assert_eq!(b.contents.to_string(), "a\n");
+  }
+}
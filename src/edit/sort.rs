@@ -0,0 +1,184 @@
+use crate::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn sort_lexically(buffer: &mut Buffer) {
+  let mut contents = buffer.selection_contents();
+  contents[..buffer.text_selection_count()].sort();
+  buffer.replace_selections(&contents);
+}
+
+pub fn sort_numerically(buffer: &mut Buffer) {
+  let mut contents = buffer.selection_contents();
+  contents[..buffer.text_selection_count()]
+    .sort_by(|a, b| compare_keys(first_number(a), first_number(b)));
+  buffer.replace_selections(&contents);
+}
+
+pub fn sort_by_regex(buffer: &mut Buffer, regex: &Regex) {
+  let mut contents = buffer.selection_contents();
+  let len = buffer.text_selection_count();
+  let keys = buffer.selections[..len]
+    .iter()
+    .map(|selection| {
+      regex
+        .find(&buffer.contents, selection.start(), selection.end())
+        .next()
+        .map(|(start, end)| buffer.contents.slice(start..=end).to_string())
+    })
+    .collect::<Vec<_>>();
+  let mut entries = keys
+    .into_iter()
+    .zip(contents.drain(..len))
+    .collect::<Vec<_>>();
+  entries.sort_by(|(a, _), (b, _)| compare_keys(a.as_ref(), b.as_ref()));
+  let sorted = entries.into_iter().map(|(_, content)| content);
+  let contents = sorted.chain(contents).collect::<Vec<_>>();
+  buffer.replace_selections(&contents);
+}
+
+pub fn reverse_selections(buffer: &mut Buffer) {
+  let mut contents = buffer.selection_contents();
+  contents[..buffer.text_selection_count()].reverse();
+  buffer.replace_selections(&contents);
+}
+
+pub fn shuffle_selections(buffer: &mut Buffer) {
+  let mut contents = buffer.selection_contents();
+  let len = buffer.text_selection_count();
+  let mut state = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_nanos() as u64)
+    .unwrap_or(0)
+    | 1;
+  // fisher-yates shuffle driven by a xorshift generator
+  for i in (1..len).rev() {
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    let j = (state % (i as u64 + 1)) as usize;
+    contents.swap(i, j);
+  }
+  buffer.replace_selections(&contents);
+}
+
+pub fn dedupe_selections(buffer: &mut Buffer) {
+  // drop selections whose contents repeat an earlier one, leaving the text alone
+  let contents = buffer.selection_contents();
+  let mut first_seen: HashMap<&str, usize> = HashMap::new();
+  let mut selections = Vec::with_capacity(contents.len());
+  let mut primary_selection = 0;
+  for (i, content) in contents.iter().enumerate() {
+    let kept = *first_seen.entry(content.as_str()).or_insert_with(|| {
+      selections.push(buffer.selections[i]);
+      selections.len() - 1
+    });
+    if i == buffer.primary_selection {
+      primary_selection = kept;
+    }
+  }
+  buffer.set_selections_and_primary(selections, primary_selection);
+}
+
+fn compare_keys<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Ordering {
+  // values without a key keep their relative order after all keyed values
+  match (a, b) {
+    (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    (Some(_), None) => Ordering::Less,
+    (None, Some(_)) => Ordering::Greater,
+    (None, None) => Ordering::Equal,
+  }
+}
+
+fn first_number(value: &str) -> Option<f64> {
+  let chars = value.char_indices().collect::<Vec<_>>();
+  let begin = chars.iter().position(|(_, ch)| ch.is_ascii_digit())?;
+  let mut start = chars[begin].0;
+  if begin > 0 && chars[begin - 1].1 == '-' {
+    start = chars[begin - 1].0;
+  }
+  let mut end = value.len();
+  let mut seen_dot = false;
+  for &(i, ch) in chars.iter().skip(begin) {
+    if ch == '.' && !seen_dot {
+      seen_dot = true;
+    } else if !ch.is_ascii_digit() {
+      end = i;
+      break;
+    }
+  }
+  value[start..end].trim_end_matches('.').parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn first_number_in_value() {
+    assert_eq!(first_number("item 42"), Some(42.0));
+    assert_eq!(first_number("x=-3.5;"), Some(-3.5));
+    assert_eq!(first_number("v1.2.3"), Some(1.2));
+    assert_eq!(first_number("7."), Some(7.0));
+    assert_eq!(first_number("none"), None);
+  }
+
+  #[test]
+  fn keyless_values_sort_last() {
+    let mut values = vec![Some(3.0), None, Some(-1.0), Some(10.0)];
+    values.sort_by(|a, b| compare_keys(*a, *b));
+    assert_eq!(values, vec![Some(-1.0), Some(3.0), Some(10.0), None]);
+  }
+
+  #[test]
+  fn sort_lexically_and_numerically() {
+    let mut b = buffer("b,c,a", selections(&[(0, 0), (2, 2), (4, 4)]));
+    sort_lexically(&mut b);
+    assert_eq!(b.contents.to_string(), "a,b,c");
+
+    let mut b = buffer("10,x,9", selections(&[(0, 1), (3, 3), (5, 5)]));
+    sort_numerically(&mut b);
+    assert_eq!(b.contents.to_string(), "9,10,x");
+    assert_eq!(ranges(&b.selections), vec![(0, 0), (2, 3), (5, 5)]);
+  }
+
+  #[test]
+  fn dedupe_keeps_text_and_first_occurrences() {
+    let mut b = buffer("a,b,a,c", selections(&[(0, 0), (2, 2), (4, 4), (6, 6)]));
+    b.primary_selection = 2;
+    dedupe_selections(&mut b);
+    assert_eq!(b.contents.to_string(), "a,b,a,c");
+    assert_eq!(ranges(&b.selections), vec![(0, 0), (2, 2), (6, 6)]);
+    assert_eq!(b.primary_selection, 0);
+  }
+
+  #[test]
+  fn dedupe_follows_unique_primary() {
+    let mut b = buffer("a,a,b", selections(&[(0, 0), (2, 2), (4, 4)]));
+    b.primary_selection = 2;
+    dedupe_selections(&mut b);
+    assert_eq!(ranges(&b.selections), vec![(0, 0), (4, 4)]);
+    assert_eq!(b.primary_selection, 1);
+  }
+
+  #[test]
+  fn end_of_file_cursor_is_not_sorted() {
+    let text = "b\na\n";
+    let ends = selections(&[(0, 0), (2, 2), (4, 4)]);
+    let mut b = buffer(text, ends.clone());
+    sort_lexically(&mut b);
+    assert_eq!(b.contents.to_string(), "a\nb\n");
+    let mut b = buffer(text, ends.clone());
+    sort_by_regex(&mut b, &Regex::new("[a-z]").unwrap());
+    assert_eq!(b.contents.to_string(), "a\nb\n");
+    let mut b = buffer(text, ends.clone());
+    reverse_selections(&mut b);
+    assert_eq!(b.contents.to_string(), "a\nb\n");
+    let mut b = buffer(text, ends);
+    shuffle_selections(&mut b);
+    assert!(["a\nb\n", "b\na\n"].contains(&b.contents.to_string().as_str()));
+    assert_eq!(ranges(&b.selections), vec![(0, 0), (2, 2), (4, 4)]);
+  }
+}
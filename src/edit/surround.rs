@@ -0,0 +1,193 @@
+use crate::*;
+use ropey::Rope;
+use std::cmp::Reverse;
+
+const QUOTES: [char; 3] = ['"', '\'', '`'];
+
+pub fn surround_pair(ch: char) -> Option<(String, String)> {
+  let (open, close) = match ch {
+    '(' | ')' => ('(', ')'),
+    '[' | ']' => ('[', ']'),
+    '{' | '}' => ('{', '}'),
+    '<' | '>' => ('<', '>'),
+    ch if QUOTES.contains(&ch) => (ch, ch),
+    _ => return None,
+  };
+  Some((open.to_string(), close.to_string()))
+}
+
+pub fn tag_pair(tag: &str) -> Option<(String, String)> {
+  let tag = tag.trim();
+  let name = tag.split_whitespace().next()?;
+  Some((format!("<{tag}>"), format!("</{name}>")))
+}
+
+pub fn add_surround(buffer: &mut Buffer, open: &str, close: &str) {
+  let len = buffer.contents.len_chars();
+  let mut edits = vec![];
+  for selection in buffer.selections.iter().rev() {
+    edits.push(((selection.end() + 1).min(len), close));
+    edits.push((selection.start().min(len), open));
+  }
+  // a stable sort keeps the opening of a later selection in front of the
+  // closing of an adjacent earlier one when both land on the same index
+  edits.sort_by_key(|&(index, _)| Reverse(index));
+  for (index, value) in edits {
+    buffer.insert(index, value);
+  }
+  buffer.commit();
+}
+
+pub fn change_surround(buffer: &mut Buffer, open: &str, close: &str) -> Result<(), String> {
+  let pairs = surrounding_pairs(buffer)?;
+  let mut edits = vec![];
+  for (o, c) in pairs {
+    edits.push((c, close));
+    edits.push((o, open));
+  }
+  edits.sort_by_key(|&(index, _)| Reverse(index));
+  for (index, value) in edits {
+    buffer.replace(index..index + 1, value);
+  }
+  buffer.commit();
+  Ok(())
+}
+
+pub fn delete_surround(buffer: &mut Buffer) -> Result<(), String> {
+  let pairs = surrounding_pairs(buffer)?;
+  let mut indices = pairs
+    .into_iter()
+    .flat_map(|(o, c)| [o, c])
+    .collect::<Vec<_>>();
+  indices.sort_by_key(|&index| Reverse(index));
+  for index in indices {
+    buffer.remove(index..index + 1);
+  }
+  buffer.commit();
+  Ok(())
+}
+
+fn surrounding_pairs(buffer: &Buffer) -> Result<Vec<(usize, usize)>, String> {
+  let mut pairs = buffer
+    .selections
+    .iter()
+    .filter_map(|selection| find_surrounding_pair(&buffer.contents, selection))
+    .collect::<Vec<_>>();
+  if pairs.is_empty() {
+    return Err("no surrounding pair found".into());
+  }
+  pairs.sort();
+  pairs.dedup();
+  Ok(pairs)
+}
+
+pub fn find_surrounding_pair(contents: &Rope, selection: &Selection) -> Option<(usize, usize)> {
+  if contents.len_chars() == 0 {
+    return None;
+  }
+  let start = selection
+    .start()
+    .min(contents.len_chars().saturating_sub(1));
+  let end = selection.end().min(contents.len_chars().saturating_sub(1));
+  let brackets = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')]
+    .into_iter()
+    .filter_map(|(open, close)| enclosing_brackets(contents, start, end, open, close));
+  let quotes = QUOTES
+    .into_iter()
+    .filter_map(|quote| enclosing_quotes(contents, start, end, quote));
+  // the innermost pair is the one opening closest to the selection
+  brackets.chain(quotes).max_by_key(|&(o, c)| (o, Reverse(c)))
+}
+
+fn enclosing_brackets(
+  contents: &Rope,
+  start: usize,
+  end: usize,
+  open: char,
+  close: char,
+) -> Option<(usize, usize)> {
+  let mut depth = 0usize;
+  let mut chars = contents.chars_at(start + 1);
+  let mut i = start + 1;
+  while let Some(ch) = chars.prev() {
+    i -= 1;
+    if ch == close && i != start {
+      depth += 1;
+    } else if ch == open {
+      if depth > 0 {
+        depth -= 1;
+        continue;
+      }
+      // an opening bracket left unclosed leaves every outer one unclosed too,
+      // so there is no point scanning to the end of the file again for them
+      match find_matching_pair(contents, i, (open, close)) {
+        Some(c) if c >= end => return Some((i, c)),
+        Some(_) => {}
+        None => return None,
+      }
+    }
+  }
+  None
+}
+
+fn enclosing_quotes(
+  contents: &Rope,
+  start: usize,
+  end: usize,
+  quote: char,
+) -> Option<(usize, usize)> {
+  let line = contents.char_to_line(start);
+  if contents.char_to_line(end) != line {
+    return None;
+  }
+  let line_begin = contents.line_to_char(line);
+  let mut quotes = vec![];
+  let mut previous = None;
+  for (i, ch) in contents.line(line).chars().enumerate() {
+    if ch == quote && previous != Some('\\') {
+      quotes.push(line_begin + i);
+    }
+    previous = Some(ch);
+  }
+  quotes
+    .chunks_exact(2)
+    .map(|pair| (pair[0], pair[1]))
+    .find(|&(o, c)| o <= start && c >= end)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  fn find(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let contents = Rope::from_str(text);
+    find_surrounding_pair(&contents, &Selection::new_at_end(start, end))
+  }
+
+  #[test]
+  fn innermost_pair() {
+    assert_eq!(find("f(a, [b], c)", 6, 6), Some((5, 7)));
+    assert_eq!(find("f(a, [b], c)", 3, 3), Some((1, 11)));
+    assert_eq!(find("f(a, \"b c\")", 7, 8), Some((5, 9)));
+    assert_eq!(find("f(a) (b)", 2, 6), None);
+    assert_eq!(find("(a)", 2, 2), Some((0, 2)));
+  }
+
+  #[test]
+  fn unclosed_outer_brackets() {
+    assert_eq!(find("((a", 2, 2), None);
+    assert_eq!(find("(( a)", 2, 2), Some((1, 4)));
+    assert_eq!(find("(a) b", 4, 4), None);
+  }
+
+  #[test]
+  fn change_and_delete_angle_brackets() {
+    let mut b = buffer("x<a>y", selections(&[(2, 2)]));
+    change_surround(&mut b, "(", ")").unwrap();
+    assert_eq!(b.contents.to_string(), "x(a)y");
+    let mut b = buffer("x<a>y", selections(&[(2, 2)]));
+    delete_surround(&mut b).unwrap();
+    assert_eq!(b.contents.to_string(), "xay");
+  }
+}
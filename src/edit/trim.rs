@@ -0,0 +1,36 @@
+use crate::*;
+
+pub fn trim_selections(buffer: &mut Buffer) -> Result<(), String> {
+  let contents = &buffer.contents;
+  let is_whitespace = |index: usize| contents.get_char(index).is_none_or(|ch| ch.is_whitespace());
+  let mut selections = Vec::with_capacity(buffer.selections.len());
+  let mut primary_selection = None;
+  for (i, selection) in buffer.selections.iter().enumerate() {
+    let mut start = selection.start();
+    let mut end = selection.end();
+    while start <= end && is_whitespace(start) {
+      start += 1;
+    }
+    while end > start && is_whitespace(end) {
+      end -= 1;
+    }
+    if start > end {
+      continue;
+    }
+    // the primary selection moves to the closest one left after it if it
+    // was made of whitespace only
+    if i >= buffer.primary_selection && primary_selection.is_none() {
+      primary_selection = Some(selections.len());
+    }
+    selections.push(match selection.side() {
+      Side::Start => Selection::new_at_start(start, end),
+      Side::End => Selection::new_at_end(start, end),
+    });
+  }
+  if selections.is_empty() {
+    return Err("no selections left after trimming".into());
+  }
+  let primary_selection = primary_selection.unwrap_or(selections.len() - 1);
+  buffer.set_selections_and_primary(selections, primary_selection);
+  Ok(())
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CommentTokens {
+  pub line: Option<&'static str>,
+  pub block: Option<(&'static str, &'static str)>,
+}
+
+pub fn comment_tokens(filename: &str) -> Option<CommentTokens> {
+  let path = Path::new(filename);
+  let name = path.file_name()?.to_str()?;
+  // files known by their whole name have no extension, dotfiles included
+  let kind = match name {
+    "Makefile" | "Dockerfile" | ".gitignore" | ".env" => name,
+    _ => path.extension()?.to_str()?,
+  };
+  let c_like = CommentTokens {
+    line: Some("//"),
+    block: Some(("/*", "*/")),
+  };
+  let hash = CommentTokens {
+    line: Some("#"),
+    block: None,
+  };
+  let tokens = match kind {
+    "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "go" | "java" | "js" | "jsx" | "ts"
+    | "tsx" | "kt" | "swift" | "scala" | "dart" | "zig" | "scss" | "less" | "proto" => c_like,
+    "sh" | "bash" | "zsh" | "py" | "rb" | "pl" | "r" | "toml" | "yaml" | "yml" | "conf" | "ini"
+    | "mk" | "cmake" | "nix" | "ex" | "exs" | "kak" | "notes" => hash,
+    "Makefile" | "Dockerfile" | ".gitignore" | ".env" => hash,
+    "lua" => CommentTokens {
+      line: Some("--"),
+      block: Some(("--[[", "]]")),
+    },
+    "hs" => CommentTokens {
+      line: Some("--"),
+      block: Some(("{-", "-}")),
+    },
+    "sql" => CommentTokens {
+      line: Some("--"),
+      block: Some(("/*", "*/")),
+    },
+    "css" => CommentTokens {
+      line: None,
+      block: Some(("/*", "*/")),
+    },
+    "html" | "xml" | "svg" | "md" | "vue" => CommentTokens {
+      line: None,
+      block: Some(("<!--", "-->")),
+    },
+    "lisp" | "el" | "clj" | "scm" | "asm" => CommentTokens {
+      line: Some(";"),
+      block: None,
+    },
+    "vim" => CommentTokens {
+      line: Some("\""),
+      block: None,
+    },
+    "tex" | "erl" => CommentTokens {
+      line: Some("%"),
+      block: None,
+    },
+    _ => return None,
+  };
+  Some(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tokens_by_extension_or_name() {
+    let line = |filename| comment_tokens(filename).and_then(|tokens| tokens.line);
+    assert_eq!(line("src/main.rs"), Some("//"));
+    assert_eq!(line("a/b/.gitignore"), Some("#"));
+    assert_eq!(line(".env"), Some("#"));
+    assert_eq!(line("Makefile"), Some("#"));
+    assert_eq!(line("index.html"), None);
+    assert!(comment_tokens("index.html").is_some());
+    assert!(comment_tokens("README").is_none());
+    assert!(comment_tokens("data.unknown").is_none());
+  }
+}
@@ -0,0 +1,124 @@
+use crate::*;
+
+const MAX_JUMPS: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct Jump {
+  selections: Vec<Selection>,
+  primary_selection: usize,
+  scroll_top: usize,
+  scroll_left: usize,
+}
+
+impl Jump {
+  pub fn new(buffer: &Buffer, window: &Window) -> Self {
+    Self {
+      selections: buffer.selections.clone(),
+      primary_selection: buffer.primary_selection,
+      scroll_top: window.scroll_top,
+      scroll_left: window.scroll_left,
+    }
+  }
+
+  pub fn restore(self, buffer: &mut Buffer, window: &mut Window) {
+    let primary_selection = self
+      .primary_selection
+      .min(self.selections.len().saturating_sub(1));
+    buffer.set_selections_and_primary(self.selections, primary_selection);
+    window.scroll_top = self.scroll_top;
+    window.scroll_left = self.scroll_left;
+  }
+}
+
+#[derive(Default)]
+pub struct Jumplist {
+  entries: Vec<Jump>,
+  index: usize,
+}
+
+impl Jumplist {
+  pub fn push(&mut self, jump: Jump) {
+    self.entries.truncate(self.index);
+    self.entries.push(jump);
+    if self.entries.len() > MAX_JUMPS {
+      self.entries.remove(0);
+    }
+    self.index = self.entries.len();
+  }
+
+  pub fn back(&mut self, current: Jump) -> Option<Jump> {
+    if self.index == 0 {
+      return None;
+    }
+    // remember where we came from so that moving forward again returns here
+    if self.index == self.entries.len() {
+      self.entries.push(current);
+    } else {
+      self.entries[self.index] = current;
+    }
+    self.index -= 1;
+    self.entries.get(self.index).cloned()
+  }
+
+  pub fn forward(&mut self, current: Jump) -> Option<Jump> {
+    if self.index + 1 >= self.entries.len() {
+      return None;
+    }
+    self.entries[self.index] = current;
+    self.index += 1;
+    self.entries.get(self.index).cloned()
+  }
+
+  pub fn adjust(&mut self, len_chars: usize, changes: &[Change]) {
+    for jump in self.entries.iter_mut() {
+      adjust_selections(&mut jump.selections, len_chars, changes);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn back_and_forward() {
+    let mut j = Jumplist::default();
+    j.push(jump(1));
+    j.push(jump(2));
+    assert_eq!(cursor(j.back(jump(3))), Some(2));
+    assert_eq!(cursor(j.back(jump(2))), Some(1));
+    assert_eq!(cursor(j.back(jump(1))), None);
+    assert_eq!(cursor(j.forward(jump(1))), Some(2));
+    assert_eq!(cursor(j.forward(jump(2))), Some(3));
+    assert_eq!(cursor(j.forward(jump(3))), None);
+    assert_eq!(cursor(j.back(jump(3))), Some(2));
+    j.push(jump(4));
+    assert_eq!(cursor(j.forward(jump(5))), None);
+    assert_eq!(cursor(j.back(jump(5))), Some(4));
+  }
+
+  #[test]
+  fn adjust_after_edits() {
+    let mut j = Jumplist::default();
+    j.push(jump(10));
+    let changes = [
+      Change::Addition(0, "hello".into()),
+      Change::Removal(2, "llo".into()),
+    ];
+    j.adjust(12, &changes);
+    assert_eq!(cursor(j.back(jump(0))), Some(12));
+  }
+
+  fn jump(cursor: usize) -> Jump {
+    Jump {
+      selections: vec![Selection::new_at_end(cursor, cursor)],
+      primary_selection: 0,
+      scroll_top: 0,
+      scroll_left: 0,
+    }
+  }
+
+  fn cursor(jump: Option<Jump>) -> Option<usize> {
+    jump.map(|j| j.selections[j.primary_selection].cursor())
+  }
+}
@@ -65,7 +65,7 @@ impl Key {
         keys.push(Self::Right);
       } else if let Some(char) = input.chars().next() {
         keys.push(Self::Char(char));
-        input = &input[1..];
+        input = &input[char.len_utf8()..];
       }
     }
     keys
@@ -138,4 +138,21 @@ mod tests {
       ],
     );
   }
+
+  #[test]
+  fn from_input_multibyte_chars() {
+    assert_eq!(
+      Key::from_input("é<RET>日本<LT>"),
+      vec![
+        Key::Char('é'),
+        Key::Enter,
+        Key::Char('日'),
+        Key::Char('本'),
+        Key::Char('<'),
+      ],
+    );
+    let keys = vec![Key::Char('ö'), Key::Char('<'), Key::Tab, Key::Char('🦀')];
+    let input = keys.iter().map(|key| key.to_input()).collect::<String>();
+    assert_eq!(Key::from_input(&input), keys);
+  }
 }
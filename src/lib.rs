@@ -1,7 +1,12 @@
+mod bracket;
 mod buffer;
 mod change;
 mod color;
+mod display;
+mod edit;
+mod filetype;
 mod history;
+mod jumplist;
 mod key;
 mod mini_editor;
 mod mode;
@@ -10,14 +15,21 @@ mod regex;
 mod registry;
 mod selection;
 mod terminal;
+#[cfg(test)]
+mod testing;
 mod ui;
 mod view;
 mod window;
 
+pub use bracket::*;
 pub use buffer::*;
 pub use change::*;
 pub use color::*;
+pub use display::*;
+pub use edit::*;
+pub use filetype::*;
 pub use history::*;
+pub use jumplist::*;
 pub use key::*;
 pub use mini_editor::*;
 pub use mode::*;
@@ -9,22 +9,47 @@ fn main() {
     let mut ui = Ui::create(theme);
     let mut views = Views::default();
     let mut registry = Registry::default();
+    if let Ok(names) = std::env::var("RUST_EDITOR_UNSAVED_REGISTERS") {
+      registry.exclude_from_saving(names.split(','));
+    }
+    let registers_path = registers_state_path();
+    if let Some(path) = &registers_path {
+      // a broken state file should not prevent editing
+      let _ = registry.load(path);
+    }
     let mut recorder = Recorder::default();
     views.add(load_buffer(filename), Window::new(ui.buffer_size()));
     'main_loop: loop {
-      if let Some(keys) = recorder.take() {
-        for key in keys {
-          let should_quit = update_application(&ui, &mut views, &mut registry, &mut recorder, key);
-          if should_quit {
-            break 'main_loop
-          }
+      // any key typed while playing back interrupts the playback
+      if recorder.has_pending() {
+        match ui.poll_pending() {
+          Some(Event::Key(_)) => {
+            let played = recorder.abort();
+            let view = views.current();
+            let toast = format!("playback interrupted after {played} keys");
+            view.mode = Box::new(Normal::with_toast(toast));
+          },
+          Some(Event::Redraw) => {
+            let view = views.current();
+            view.window.set_size(ui.buffer_size());
+          },
+          None => {},
+        }
+      }
+      if let Some(key) = recorder.next_key() {
+        let should_quit = update_application(&ui, &mut views, &mut registry, &mut recorder, key);
+        if should_quit {
+          break 'main_loop
         }
       } else {
         let view = views.current();
-        ui.render(view);
+        ui.render(view, recorder.recording());
         let event = ui.poll();
         match event {
           Event::Key(key) => {
+            // only keys typed by the user are recorded, replayed keys would
+            // otherwise be played twice
+            recorder.capture(key);
             let should_quit = update_application(&ui, &mut views, &mut registry, &mut recorder, key);
             if should_quit {
               break 'main_loop
@@ -49,6 +74,12 @@ fn main() {
         view.window.keep_cursor_visible = true;
       }
     }
+    let saved = registers_path.map(|path| registry.save(&path));
+    // leave the terminal before reporting so that the message stays visible
+    drop(ui);
+    if let Some(Err(e)) = saved {
+      eprintln!("could not save registers: {e}");
+    }
   });
   if let Err(e) = result {
     resume_unwind(e);
@@ -85,6 +116,7 @@ fn load_theme() -> Theme {
     selection_secondary_face: (ramp[1], ramp[0]),
     cursor_primary_face: (ramp[3], ramp[0]),
     cursor_secondary_face: (ramp[2], ramp[0]),
+    matching_bracket_face: (ramp[1], ramp[3]),
     status_face: (ramp[0], ramp[3]),
     new_line_char: '¬',
     end_of_file_char: 'Ω',
@@ -105,13 +137,52 @@ fn update_application(
     &mut view.window,
     key,
   );
+  let edits = view.buffer.take_edits();
+  // undo and redo change the buffer too, but are not worth repeating
+  recorder.track(key, view.buffer.take_recorded());
+  view.jumps.adjust(view.buffer.contents.len_chars(), &edits);
+  registry.adjust(
+    view.buffer.filename.as_deref(),
+    view.buffer.contents.len_chars(),
+    &edits,
+  );
   for command in commands {
     match command {
       UpdateCommand::SwitchMode(next_mode) => {
         let view = views.current();
         view.mode = next_mode;
       },
-      UpdateCommand::SendKeys(keys) => recorder.add(keys),
+      UpdateCommand::SendKeys(keys) => {
+        if let Err(error) = recorder.add(keys) {
+          fail(views, recorder, key, error);
+        }
+      },
+      UpdateCommand::Fail(error) => fail(views, recorder, key, error),
+      UpdateCommand::ToggleRecording(name) => match recorder.stop_recording() {
+        Some((name, keys)) => {
+          let input = keys.iter().map(|key| key.to_input()).collect::<String>();
+          registry.set(&name, Register::Content(vec![input]));
+        }
+        None => recorder.start_recording(name),
+      },
+      UpdateCommand::PushJump(jump) => {
+        let view = views.current();
+        view.jumps.push(jump);
+      },
+      UpdateCommand::JumpBack => {
+        let view = views.current();
+        let current = Jump::new(&view.buffer, &view.window);
+        if let Some(jump) = view.jumps.back(current) {
+          jump.restore(&mut view.buffer, &mut view.window);
+        }
+      },
+      UpdateCommand::JumpForward => {
+        let view = views.current();
+        let current = Jump::new(&view.buffer, &view.window);
+        if let Some(jump) = view.jumps.forward(current) {
+          jump.restore(&mut view.buffer, &mut view.window);
+        }
+      },
       UpdateCommand::ViewPrev => views.prev(),
       UpdateCommand::ViewNext => views.next(),
       UpdateCommand::Open(filename) => {
@@ -136,5 +207,24 @@ fn update_application(
       UpdateCommand::Quit => return true,
     }
   }
+  // a command is complete once normal mode waits for a new one without a
+  // pending target register
+  if views.current().mode.is_idle() && registry.get("target").is_none() {
+    if let Some(keys) = recorder.finish_command() {
+      let input = keys.iter().map(|key| key.to_input()).collect::<String>();
+      registry.set("repeat", Register::Content(vec![input]));
+    }
+  }
   false
 }
+
+fn fail(views: &mut Views, recorder: &mut Recorder, key: Key, error: String) {
+  let toast = if recorder.is_playing() {
+    let position = recorder.abort();
+    format!("playback stopped at key {position} ({}): {error}", key.to_input())
+  } else {
+    error
+  };
+  let view = views.current();
+  view.mode = Box::new(Normal::with_toast(toast));
+}
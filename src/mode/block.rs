@@ -0,0 +1,36 @@
+use crate::*;
+
+pub struct Block;
+
+impl Block {
+  pub fn switch_to() -> UpdateCommand {
+    UpdateCommand::SwitchMode(Box::new(Self))
+  }
+}
+
+impl Mode for Block {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    _registry: &mut Registry,
+    window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    use crate::key::Key::*;
+    let pad = match key {
+      Esc => return vec![Normal::switch_to()],
+      Char('s') => false,
+      Char('p') => true,
+      _ => return vec![],
+    };
+    let jump = Jump::new(buffer, window);
+    match block_selections(buffer, pad) {
+      Ok(()) => vec![Normal::switch_to(), UpdateCommand::PushJump(jump)],
+      Err(e) => vec![UpdateCommand::Fail(e)],
+    }
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    "block > [s]kip short lines [p]ad short lines".into()
+  }
+}
@@ -0,0 +1,39 @@
+use crate::*;
+
+pub struct ChangeCase;
+
+impl ChangeCase {
+  pub fn switch_to() -> UpdateCommand {
+    UpdateCommand::SwitchMode(Box::new(Self))
+  }
+}
+
+impl Mode for ChangeCase {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    _registry: &mut Registry,
+    _window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    use crate::key::Key::*;
+    let case = match key {
+      Esc => return vec![Normal::switch_to()],
+      Char('u') => Case::Upper,
+      Char('l') => Case::Lower,
+      Char('~') => Case::Swap,
+      Char('t') => Case::Title,
+      Char('s') => Case::Snake,
+      Char('c') => Case::Camel,
+      Char('p') => Case::Pascal,
+      Char('k') => Case::Kebab,
+      _ => return vec![],
+    };
+    convert_case(buffer, case);
+    vec![Normal::switch_to()]
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    "case > [u]pper [l]ower [~]swap [t]itle [s]nake [c]amel [p]ascal [k]ebab".into()
+  }
+}
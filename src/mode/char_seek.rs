@@ -0,0 +1,208 @@
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub struct CharSeek {
+  reverse: bool,
+  extend: bool,
+  till: bool,
+  count: usize,
+}
+
+impl CharSeek {
+  pub fn switch_to(reverse: bool, extend: bool, count: usize) -> UpdateCommand {
+    let mode = Self {
+      reverse,
+      extend,
+      till: false,
+      count,
+    };
+    UpdateCommand::SwitchMode(Box::new(mode))
+  }
+}
+
+impl Mode for CharSeek {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    registry: &mut Registry,
+    _window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    use crate::key::Key::*;
+    let ch = match key {
+      Esc => return vec![Normal::switch_to()],
+      Tab => {
+        self.till = !self.till;
+        return vec![];
+      }
+      Enter => '\n',
+      Char(ch) => ch,
+      _ => return vec![],
+    };
+    let seek = LastCharSeek {
+      ch,
+      reverse: self.reverse,
+      extend: self.extend,
+      till: self.till,
+    };
+    registry.set(CHAR_SEEK_REGISTER, seek.to_register());
+    match seek_char(buffer, &seek, self.count) {
+      Ok(()) => vec![Normal::switch_to()],
+      Err(e) => vec![UpdateCommand::Fail(e)],
+    }
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    let action = if self.extend { "extend" } else { "move" };
+    let direction = if self.reverse { "backward" } else { "forward" };
+    let kind = if self.till { "till" } else { "to" };
+    format!("{action} {direction} {kind} char > [tab] toggle till").into()
+  }
+}
+
+pub const CHAR_SEEK_REGISTER: &str = "char_seek";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastCharSeek {
+  pub ch: char,
+  pub reverse: bool,
+  pub extend: bool,
+  pub till: bool,
+}
+
+impl LastCharSeek {
+  // stored as plain content so that it can be inspected and edited like any
+  // other register
+  fn to_register(&self) -> Register {
+    let flag = |set: bool, on: &str, off: &str| if set { on } else { off }.to_string();
+    Register::Content(vec![
+      self.ch.to_string(),
+      flag(self.reverse, "backward", "forward"),
+      flag(self.extend, "extend", "move"),
+      flag(self.till, "till", "to"),
+    ])
+  }
+
+  pub fn from_registry(registry: &Registry) -> Option<Self> {
+    let Some(Register::Content(parts)) = registry.get(CHAR_SEEK_REGISTER) else {
+      return None;
+    };
+    let [ch, direction, action, kind] = parts.as_slice() else {
+      return None;
+    };
+    let mut chars = ch.chars();
+    let (Some(ch), None) = (chars.next(), chars.next()) else {
+      return None;
+    };
+    Some(Self {
+      ch,
+      reverse: direction == "backward",
+      extend: action == "extend",
+      till: kind == "till",
+    })
+  }
+}
+
+pub fn seek_char(buffer: &mut Buffer, seek: &LastCharSeek, count: usize) -> Result<(), String> {
+  let mut found = false;
+  let selections = buffer
+    .selections
+    .iter()
+    .map(|selection| {
+      let Some(target) = find_char(buffer, selection.cursor(), seek, count) else {
+        return *selection;
+      };
+      found = true;
+      if seek.extend {
+        selection.extend_to(target)
+      } else {
+        Selection::new_at_end(target, target)
+      }
+    })
+    .collect();
+  if !found {
+    return Err(format!("character {:?} not found", seek.ch));
+  }
+  buffer.set_selections_and_primary(selections, buffer.primary_selection);
+  Ok(())
+}
+
+fn find_char(buffer: &Buffer, cursor: usize, seek: &LastCharSeek, count: usize) -> Option<usize> {
+  let contents = &buffer.contents;
+  // seeking till a character skips the one right next to the cursor, so that
+  // repeating the seek does not get stuck in front of it
+  let skip = if seek.till { 2 } else { 1 };
+  if seek.reverse {
+    let end = cursor.min(contents.len_chars()).checked_sub(skip - 1)?;
+    let mut chars = contents.chars_at(end);
+    let mut index = end;
+    let mut remaining = count;
+    while let Some(ch) = chars.prev() {
+      index -= 1;
+      if ch == seek.ch {
+        remaining -= 1;
+        if remaining == 0 {
+          return Some(if seek.till { index + 1 } else { index });
+        }
+      }
+    }
+    None
+  } else {
+    let start = cursor + skip;
+    if start > contents.len_chars() {
+      return None;
+    }
+    contents
+      .chars_at(start)
+      .enumerate()
+      .filter(|&(_, ch)| ch == seek.ch)
+      .nth(count.saturating_sub(1))
+      .map(|(i, _)| if seek.till { start + i - 1 } else { start + i })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  fn find(
+    text: &str,
+    cursor: usize,
+    ch: char,
+    reverse: bool,
+    till: bool,
+    count: usize,
+  ) -> Option<usize> {
+    let b = buffer(text, selections(&[(cursor, cursor)]));
+    let seek = LastCharSeek {
+      ch,
+      reverse,
+      extend: false,
+      till,
+    };
+    find_char(&b, cursor, &seek, count)
+  }
+
+  #[test]
+  fn forward_and_backward() {
+    assert_eq!(find("a,b,c,d", 0, ',', false, false, 1), Some(1));
+    assert_eq!(find("a,b,c,d", 1, ',', false, false, 1), Some(3));
+    assert_eq!(find("a,b,c,d", 0, ',', false, false, 3), Some(5));
+    assert_eq!(find("a,b,c,d", 0, ',', false, false, 4), None);
+    assert_eq!(find("a,b,c,d", 6, ',', true, false, 1), Some(5));
+    assert_eq!(find("a,b,c,d", 5, ',', true, false, 2), Some(1));
+    assert_eq!(find("a,b,c,d", 1, ',', true, false, 1), None);
+  }
+
+  #[test]
+  fn till() {
+    assert_eq!(find("a,b,c,d", 0, ',', false, true, 1), Some(2));
+    assert_eq!(find("a,b,c,d", 0, 'c', false, true, 1), Some(3));
+    // repeating from right before a match moves on to the next one
+    assert_eq!(find("a,b,c,d", 2, ',', false, true, 1), Some(4));
+    assert_eq!(find("a,b,c,d", 6, ',', true, true, 1), Some(4));
+    assert_eq!(find("a,b,c,d", 6, 'b', true, true, 1), Some(3));
+    assert_eq!(find("a,b,c,d", 4, ',', true, true, 1), Some(2));
+  }
+}
@@ -0,0 +1,76 @@
+use crate::*;
+
+#[derive(Debug, Clone, Default)]
+pub struct Cursors {
+  count: Option<usize>,
+}
+
+impl Cursors {
+  pub fn switch_to() -> UpdateCommand {
+    UpdateCommand::SwitchMode(Box::new(Self::default()))
+  }
+}
+
+impl Mode for Cursors {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    _registry: &mut Registry,
+    _window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    use crate::key::Key::*;
+    match key {
+      Esc | Enter => return vec![Normal::switch_to()],
+      Char(ch @ '0'..='9') => {
+        let digit = ch.to_digit(10).expect("should convert ascii digit") as usize;
+        let count = self.count.unwrap_or(0);
+        self.count = Some(count.saturating_mul(10).saturating_add(digit));
+      }
+      Char('j') => copy_primary(buffer, 1, self.count.take()),
+      Char('k') => copy_primary(buffer, -1, self.count.take()),
+      Char('J') => copy_all(buffer, 1, self.count.take()),
+      Char('K') => copy_all(buffer, -1, self.count.take()),
+      _ => {}
+    }
+    vec![]
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    match self.count {
+      Some(count) => format!("cursors {count}").into(),
+      None => "cursors".into(),
+    }
+  }
+}
+
+fn copy_primary(buffer: &mut Buffer, delta: isize, count: Option<usize>) {
+  for _ in 0..count.unwrap_or(1) {
+    let Some(copy) = buffer
+      .primary_selection()
+      .copy_by_line(&buffer.contents, delta)
+    else {
+      break;
+    };
+    let mut selections = buffer.selections.clone();
+    selections.push(copy);
+    let primary_selection = selections.len() - 1;
+    buffer.set_selections_and_primary(selections, primary_selection);
+  }
+}
+
+fn copy_all(buffer: &mut Buffer, delta: isize, count: Option<usize>) {
+  for _ in 0..count.unwrap_or(1) {
+    let mut selections = buffer.selections.clone();
+    for selection in buffer.selections.iter() {
+      if let Some(copy) = selection.copy_by_line(&buffer.contents, delta) {
+        selections.push(copy);
+      }
+    }
+    let previous_len = buffer.selections.len();
+    buffer.set_selections_and_primary(selections, buffer.primary_selection);
+    if buffer.selections.len() == previous_len {
+      break;
+    }
+  }
+}
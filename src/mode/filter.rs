@@ -30,8 +30,8 @@ impl Mode for Filter {
   fn update(
     &mut self,
     buffer: &mut Buffer,
-    _registry: &mut Registry,
-    _window: &mut Window,
+    registry: &mut Registry,
+    window: &mut Window,
     key: Key,
   ) -> Vec<UpdateCommand> {
     match self.editor.update(key) {
@@ -43,11 +43,16 @@ impl Mode for Filter {
           true => reject(&buffer.contents, &buffer.selections, &command),
           false => accept(&buffer.contents, &buffer.selections, &command),
         };
-        if let ModeResult::Ok(selections) = result {
-          buffer.primary_selection = selections.len().saturating_sub(1);
-          buffer.set_selections(selections);
-        }
-        return vec![Normal::switch_to()];
+        return match result {
+          ModeResult::Ok(selections) if selections.is_empty() => {
+            vec![UpdateCommand::Fail(format!("no selections left for {command}"))]
+          }
+          ModeResult::Ok(selections) => {
+            apply_pattern_selections(buffer, registry, window, &command, false, selections)
+          }
+          ModeResult::Error => vec![UpdateCommand::Fail(format!("error: invalid pattern {command}"))],
+          ModeResult::Empty => vec![Normal::switch_to()],
+        };
       },
       MiniEditorCommand::None => { },
     }
@@ -0,0 +1,170 @@
+use crate::*;
+use ropey::Rope;
+
+#[derive(Debug, Clone)]
+pub struct Goto {
+  extend: bool,
+  editor: MiniEditor,
+  preview: Option<Vec<Selection>>,
+}
+
+impl Goto {
+  pub fn switch_to(extend: bool) -> UpdateCommand {
+    let mode = Self {
+      extend,
+      editor: Default::default(),
+      preview: None,
+    };
+    UpdateCommand::SwitchMode(Box::new(mode))
+  }
+}
+
+impl Mode for Goto {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    _registry: &mut Registry,
+    window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    if self.editor.value.len_chars() == 0 {
+      if let Key::Char(ch) = key {
+        match find_target(buffer, window, ch) {
+          Some(Ok(target)) => {
+            let jump = Jump::new(buffer, window);
+            goto(buffer, self.extend, target);
+            return vec![Normal::switch_to(), UpdateCommand::PushJump(jump)];
+          }
+          Some(Err(error)) => return vec![UpdateCommand::Fail(error)],
+          None => {}
+        }
+      }
+    }
+    match self.editor.update(key) {
+      MiniEditorCommand::Cancel => return vec![Normal::switch_to()],
+      MiniEditorCommand::Update => {
+        let position = self.editor.value.to_string();
+        self.preview = parse_position(&buffer.contents, &position).map(|index| {
+          let mut selections = preview(buffer, self.extend, index);
+          merge_overlapping_selections(&mut selections);
+          selections
+        });
+      }
+      MiniEditorCommand::Submit => {
+        let position = self.editor.value.to_string();
+        return match parse_position(&buffer.contents, &position) {
+          Some(index) => {
+            let jump = Jump::new(buffer, window);
+            goto(buffer, self.extend, index);
+            vec![Normal::switch_to(), UpdateCommand::PushJump(jump)]
+          }
+          None => vec![UpdateCommand::Fail(format!(
+            "error: invalid position {position}"
+          ))],
+        };
+      }
+      MiniEditorCommand::None => {}
+    }
+    vec![]
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    let action = if self.extend { "extend" } else { "goto" };
+    format!("{action} > {}", self.editor.value).into()
+  }
+
+  fn preview_selections(&self) -> Option<&Vec<Selection>> {
+    self.preview.as_ref()
+  }
+}
+
+// None when the char does not name a target and is part of a position
+fn find_target(buffer: &Buffer, window: &Window, ch: char) -> Option<Result<usize, String>> {
+  let contents = &buffer.contents;
+  let last_line = contents.len_lines().saturating_sub(1);
+  let target = match ch {
+    'k' => 0,
+    'j' => contents.len_chars(),
+    'K' => contents.line_to_char(window.scroll_top.min(last_line)),
+    'J' => {
+      let bottom = window
+        .scroll_top
+        .saturating_add(window.height.saturating_sub(1));
+      contents.line_to_char(bottom.min(last_line))
+    }
+    'm' => match find_matching_bracket(contents, buffer.primary_selection().cursor()) {
+      Some(index) => index,
+      None => return Some(Err("no matching bracket found".into())),
+    },
+    _ => return None,
+  };
+  Some(Ok(target))
+}
+
+fn parse_position(contents: &Rope, position: &str) -> Option<usize> {
+  let (line, col) = match position.split_once(':') {
+    Some((line, col)) => (line.parse::<usize>().ok()?, col.parse::<usize>().ok()?),
+    None => (position.parse::<usize>().ok()?, 0),
+  };
+  let line = line.min(contents.len_lines().saturating_sub(1));
+  let line_begin = contents.line_to_char(line);
+  let line_len = contents.line(line).len_chars();
+  Some(line_begin + col.min(line_len.saturating_sub(1)))
+}
+
+fn preview(buffer: &Buffer, extend: bool, index: usize) -> Vec<Selection> {
+  let mut selections = buffer.selections.clone();
+  selections[buffer.primary_selection] = move_selection(buffer.primary_selection(), extend, index);
+  selections
+}
+
+fn goto(buffer: &mut Buffer, extend: bool, index: usize) {
+  let selections = preview(buffer, extend, index);
+  buffer.set_selections_and_primary(selections, buffer.primary_selection);
+}
+
+fn move_selection(selection: &Selection, extend: bool, index: usize) -> Selection {
+  if extend {
+    selection.extend_to(index)
+  } else {
+    Selection::new_at_end(index, index)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn positions_are_clamped_to_contents() {
+    let contents = Rope::from_str("ab\ncde\nf");
+    assert_eq!(parse_position(&contents, "0"), Some(0));
+    assert_eq!(parse_position(&contents, "1"), Some(3));
+    assert_eq!(parse_position(&contents, "1:2"), Some(5));
+    assert_eq!(parse_position(&contents, "1:9"), Some(6));
+    assert_eq!(parse_position(&contents, "99"), Some(7));
+    assert_eq!(parse_position(&contents, "2:5"), Some(7));
+  }
+
+  #[test]
+  fn malformed_positions() {
+    let contents = Rope::from_str("ab\ncde\nf");
+    for position in ["", "a", "1:", ":1", "-1", "1:2:3", "1 "] {
+      assert_eq!(parse_position(&contents, position), None, "{position:?}");
+    }
+  }
+
+  #[test]
+  fn missing_bracket_fails() {
+    let mut b = buffer("(a", selections(&[(0, 0)]));
+    let UpdateCommand::SwitchMode(mut mode) = Goto::switch_to(false) else {
+      panic!("goto should switch modes");
+    };
+    let mut registry = Registry::default();
+    let mut window = Window::new((80, 24));
+    let commands = mode.update(&mut b, &mut registry, &mut window, Key::Char('m'));
+    assert!(matches!(commands.as_slice(), [UpdateCommand::Fail(_)]));
+    assert_eq!(mode.status(), "goto > ");
+  }
+}
@@ -24,7 +24,10 @@ impl Mode for Insert {
         return vec![Normal::switch_to()];
       }
       Backspace => buffer.apply_operations(&[Op::Remove]),
-      Tab => buffer.apply_operations(&[Op::InsertStr("  ")]),
+      Tab => {
+        let indent_unit = buffer.indent_unit.clone();
+        buffer.apply_operations(&[Op::InsertStr(&indent_unit)]);
+      }
       Enter => buffer.apply_operations(&[Op::InsertChar('\n')]),
       Char(ch) => buffer.apply_operations(&[Op::InsertChar(ch)]),
       _ => {}
@@ -0,0 +1,52 @@
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub struct BreakLines {
+  keep_indent: bool,
+  editor: MiniEditor,
+}
+
+impl BreakLines {
+  pub fn switch_to() -> UpdateCommand {
+    let mode = Self {
+      keep_indent: true,
+      editor: Default::default(),
+    };
+    UpdateCommand::SwitchMode(Box::new(mode))
+  }
+}
+
+impl Mode for BreakLines {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    _registry: &mut Registry,
+    _window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    if key == Key::Tab {
+      self.keep_indent = !self.keep_indent;
+      return vec![];
+    }
+    match self.editor.update(key) {
+      MiniEditorCommand::Cancel => return vec![Normal::switch_to()],
+      MiniEditorCommand::Submit => {
+        let delimiter = self.editor.value.to_string();
+        break_lines(buffer, &delimiter, self.keep_indent);
+        return vec![Normal::switch_to()];
+      }
+      MiniEditorCommand::Update => {}
+      MiniEditorCommand::None => {}
+    }
+    vec![]
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    let indent = if self.keep_indent {
+      "[indent]"
+    } else {
+      "[no indent]"
+    };
+    format!("break lines {indent} > {}", self.editor.value).into()
+  }
+}
@@ -1,19 +1,37 @@
+mod block;
+mod case;
+mod char_seek;
+mod cursors;
 mod filter;
+mod goto;
 mod insert;
+mod lines;
 mod normal;
 mod open;
+mod order;
 mod pipe;
+mod restore;
 mod seek;
 mod split;
+mod surround;
 mod target;
 
+pub use block::*;
+pub use case::*;
+pub use char_seek::*;
+pub use cursors::*;
 pub use filter::*;
+pub use goto::*;
 pub use insert::*;
+pub use lines::*;
 pub use normal::*;
 pub use open::*;
+pub use order::*;
 pub use pipe::*;
+pub use restore::*;
 pub use seek::*;
 pub use split::*;
+pub use surround::*;
 pub use target::*;
 
 use crate::*;
@@ -21,6 +39,11 @@ use crate::*;
 pub enum UpdateCommand {
   SwitchMode(Box<dyn Mode>),
   SendKeys(Vec<Key>),
+  Fail(String),
+  ToggleRecording(String),
+  PushJump(Jump),
+  JumpBack,
+  JumpForward,
   ViewPrev,
   ViewNext,
   Open(String),
@@ -39,6 +62,11 @@ pub trait Mode {
 
   fn status(&self) -> CowStr<'_>;
 
+  // whether the mode is waiting for the first key of a new command
+  fn is_idle(&self) -> bool {
+    false
+  }
+
   fn preview_selections(&self) -> Option<&Vec<Selection>> {
     None
   }
@@ -1,8 +1,11 @@
 use crate::*;
 
+const MAX_COUNT: usize = 99_999;
+
 #[derive(Default)]
 pub struct Normal {
   toast: Option<String>,
+  count: Option<usize>,
 }
 
 impl Normal {
@@ -10,10 +13,11 @@ impl Normal {
     UpdateCommand::SwitchMode(Box::new(Self::default()))
   }
 
-  pub fn switch_to_with_toast(toast: impl Into<String>) -> UpdateCommand {
-    UpdateCommand::SwitchMode(Box::new(Self {
+  pub fn with_toast(toast: impl Into<String>) -> Self {
+    Self {
       toast: Some(toast.into()),
-    }))
+      count: None,
+    }
   }
 }
 
@@ -27,18 +31,27 @@ impl Mode for Normal {
   ) -> Vec<UpdateCommand> {
     use crate::key::Key::*;
     self.toast = None;
+    if let Char(ch @ '0'..='9') = key {
+      if ch != '0' || self.count.is_some() {
+        let digit = ch.to_digit(10).expect("should convert ascii digit") as usize;
+        let count = self.count.unwrap_or(0);
+        self.count = Some((count * 10 + digit).min(MAX_COUNT));
+        return vec![];
+      }
+    }
+    let count = self.count.take().unwrap_or(1);
+    let delta = count as isize;
+    let mut error = None;
     match key {
       // Meta actions
       Char('Q') => return vec![UpdateCommand::Quit],
       Char('W') => {
-        if buffer.filename.is_some() {
-          self.toast = if buffer.save() {
-            Some("file saved!".into())
-          } else {
-            Some("error: could not save file".into())
-          };
+        if buffer.filename.is_none() {
+          error = Some("scratch buffers cannot be saved".into());
+        } else if buffer.save() {
+          self.toast = Some("file saved!".into());
         } else {
-          self.toast = Some("scratch buffers cannot be saved".into());
+          error = Some("error: could not save file".into());
         }
       }
       Char('[') => return vec![UpdateCommand::ViewPrev],
@@ -47,14 +60,20 @@ impl Mode for Normal {
       Char('C') => return vec![UpdateCommand::Close],
       Char(' ') => {
         let name = take_register_target(registry).unwrap_or_else(|| "playback".to_string());
-        if let Some(Register::Content(contents)) = registry.get(&name) {
-          if let Some(contents) = contents.first() {
-            let keys = Key::from_input(contents);
-            return vec![UpdateCommand::SendKeys(keys)];
-          }
+        match register_keys(registry, &name, count) {
+          Some(keys) => return vec![UpdateCommand::SendKeys(keys)],
+          None => error = Some(format!("error: register {name} holds no keys")),
         }
+      }
+      Enter => match register_keys(registry, "repeat", count) {
+        Some(keys) => return vec![UpdateCommand::SendKeys(keys)],
+        None => error = Some("no edit to repeat".into()),
       },
       Char('e') => return vec![Target::switch_to()],
+      Char('q') => {
+        let name = take_register_target(registry).unwrap_or_else(|| "playback".to_string());
+        return vec![UpdateCommand::ToggleRecording(name)];
+      }
 
       // Content modifications
       Char('d') => {
@@ -69,38 +88,87 @@ impl Mode for Normal {
       Char('X') => {
         let name = take_register_target(registry).unwrap_or_else(|| "clipboard".to_string());
         if let Some(Register::Content(contents)) = registry.get(&name) {
-          paste(buffer, contents);
+          let contents = contents.iter().map(|c| c.repeat(count)).collect::<Vec<_>>();
+          paste(buffer, &contents);
         }
       }
-      Char('z') => undo(buffer),
-      Char('Z') => redo(buffer),
+      Char('z') => (0..count).for_each(|_| undo(buffer)),
+      Char('Z') => (0..count).for_each(|_| redo(buffer)),
       Char('r') => return vec![Pipe::switch_to()],
+      Char('&') => align(buffer, Side::End),
+      Char('A') => align(buffer, Side::Start),
+      Char(')') => rotate_contents(buffer, 1),
+      Char('(') => rotate_contents(buffer, -1),
+      Char('o') => return vec![Order::switch_to()],
+      Char('+') => increment_numbers(buffer, delta as i64),
+      Char('-') => increment_numbers(buffer, -(delta as i64)),
+      Char('=') => number_selections(buffer, 1),
+      Char('U') => return vec![ChangeCase::switch_to()],
+      Char('^') => join_lines(buffer),
+      Char('|') => return vec![BreakLines::switch_to()],
+      Char('>') => indent_lines(buffer),
+      Char('<') => dedent_lines(buffer),
+      Char('w') => return vec![Surround::switch_to()],
 
       // Anchor movements
-      Char('h') => buffer.apply_operations(&[Op::MoveByChar(-1), Op::Collapse]),
-      Char('j') => buffer.apply_operations(&[Op::MoveByLine(1), Op::Collapse]),
-      Char('k') => buffer.apply_operations(&[Op::MoveByLine(-1), Op::Collapse]),
-      Char('l') => buffer.apply_operations(&[Op::MoveByChar(1), Op::Collapse]),
-      Char('H') => buffer.apply_operations(&[Op::MoveByChar(-1)]),
-      Char('J') => buffer.apply_operations(&[Op::MoveByLine(1)]),
-      Char('K') => buffer.apply_operations(&[Op::MoveByLine(-1)]),
-      Char('L') => buffer.apply_operations(&[Op::MoveByChar(1)]),
+      Char('h') => buffer.apply_operations(&[Op::MoveByChar(-delta), Op::Collapse]),
+      Char('j') => buffer.apply_operations(&[Op::MoveByLine(delta), Op::Collapse]),
+      Char('k') => buffer.apply_operations(&[Op::MoveByLine(-delta), Op::Collapse]),
+      Char('l') => buffer.apply_operations(&[Op::MoveByChar(delta), Op::Collapse]),
+      Char('H') => buffer.apply_operations(&[Op::MoveByChar(-delta)]),
+      Char('J') => buffer.apply_operations(&[Op::MoveByLine(delta)]),
+      Char('K') => buffer.apply_operations(&[Op::MoveByLine(-delta)]),
+      Char('L') => buffer.apply_operations(&[Op::MoveByChar(delta)]),
+      Char('\'') => return vec![CharSeek::switch_to(false, false, count)],
+      Char('"') => return vec![CharSeek::switch_to(true, false, count)],
+      Char('`') => return vec![CharSeek::switch_to(false, true, count)],
+      Char('~') => return vec![CharSeek::switch_to(true, true, count)],
+      Char(ch @ (';' | ',')) => match LastCharSeek::from_registry(registry) {
+        Some(mut seek) => {
+          seek.reverse ^= ch == ',';
+          error = seek_char(buffer, &seek, count).err();
+        }
+        None => error = Some("no character seek to repeat".into()),
+      },
       Char('g') => return vec![Seek::switch_to(false)],
       Char('G') => return vec![Seek::switch_to(true)],
+      Char(ch @ ('/' | '\\')) => {
+        let jump = Jump::new(buffer, window);
+        match repeat_seek(buffer, registry, ch == '\\', count) {
+          Ok(()) => return vec![UpdateCommand::PushJump(jump)],
+          Err(e) => error = Some(e),
+        }
+      }
       Char('b') => buffer.apply_operations(&[Op::Swap]),
       Char('n') => buffer.apply_operations(&[Op::Collapse]),
-      Char('p') => move_by_window_page(buffer, window, 1),
-      Char('P') => move_by_window_page(buffer, window, -1),
+      Char('m') => return vec![Goto::switch_to(false)],
+      Char('M') => return vec![Goto::switch_to(true)],
+      Char('p') => {
+        let jump = Jump::new(buffer, window);
+        move_by_window_page(buffer, window, delta);
+        return vec![UpdateCommand::PushJump(jump)];
+      }
+      Char('P') => {
+        let jump = Jump::new(buffer, window);
+        move_by_window_page(buffer, window, -delta);
+        return vec![UpdateCommand::PushJump(jump)];
+      }
+      Char('{') => return vec![UpdateCommand::JumpBack],
+      Char('}') => return vec![UpdateCommand::JumpForward],
 
       // Selection manipulation
       Char('u') => {
-        buffer.set_selections(vec![Selection::new_at_end(0, buffer.contents.len_chars())])
+        let jump = Jump::new(buffer, window);
+        buffer.set_selections(vec![Selection::new_at_end(0, buffer.contents.len_chars())]);
+        return vec![UpdateCommand::PushJump(jump)];
       }
       Char('t') => {
-        buffer.primary_selection = wrap_add(buffer.selections.len(), buffer.primary_selection, 1)
+        buffer.primary_selection =
+          wrap_add(buffer.selections.len(), buffer.primary_selection, delta)
       }
       Char('T') => {
-        buffer.primary_selection = wrap_add(buffer.selections.len(), buffer.primary_selection, -1);
+        buffer.primary_selection =
+          wrap_add(buffer.selections.len(), buffer.primary_selection, -delta);
       }
       Char('y') => buffer.set_selections(vec![*buffer.primary_selection()]),
       Char('Y') => {
@@ -113,6 +181,43 @@ impl Mode for Normal {
           .collect();
         buffer.set_selections(selections);
       }
+      Char('c') => return vec![Cursors::switch_to()],
+      Char('B') => return vec![Block::switch_to()],
+      Char('_') => error = trim_selections(buffer).err(),
+      Char('#') => error = toggle_comments(buffer).err(),
+      Char('N') => error = select_next_occurrence(buffer).err(),
+      Char(ch @ ('%' | '$')) => {
+        let jump = Jump::new(buffer, window);
+        match select_matching_brackets(buffer, ch == '$') {
+          Ok(()) => return vec![UpdateCommand::PushJump(jump)],
+          Err(e) => error = Some(e),
+        }
+      }
+      Char('*') => {
+        let jump = Jump::new(buffer, window);
+        match select_all_occurrences(buffer) {
+          Ok(()) => return vec![UpdateCommand::PushJump(jump)],
+          Err(e) => error = Some(e),
+        }
+      }
+      Char('i') => {
+        let name = take_register_target(registry).unwrap_or_else(|| "selections".to_string());
+        let value = Register::Selections(
+          buffer.filename.clone(),
+          buffer.selections.clone(),
+          buffer.primary_selection,
+        );
+        registry.set(&name, value);
+      }
+      Char('I') => {
+        let name = take_register_target(registry).unwrap_or_else(|| "selections".to_string());
+        if saved_selections(buffer, registry, &name).is_some() {
+          return vec![Restore::switch_to(name)];
+        }
+        error = Some(format!(
+          "error: register {name} holds no selections for this buffer"
+        ));
+      }
       Char('s') => return vec![Split::switch_to(false)],
       Char('S') => return vec![Split::switch_to(true)],
       Char('f') => return vec![Filter::switch_to(false)],
@@ -139,25 +244,28 @@ impl Mode for Normal {
 
       _ => {}
     }
-    vec![]
+    match error {
+      Some(error) => vec![UpdateCommand::Fail(error)],
+      None => vec![],
+    }
+  }
+
+  fn is_idle(&self) -> bool {
+    self.count.is_none()
   }
 
   fn status(&self) -> CowStr<'_> {
-    match &self.toast {
-      Some(toast) => toast.into(),
-      None => "normal".into(),
+    match (&self.toast, self.count) {
+      (Some(toast), _) => toast.into(),
+      (None, Some(count)) => format!("normal {count}").into(),
+      (None, None) => "normal".into(),
     }
   }
 }
 
-fn move_by_window_page(buffer: &mut Buffer, window: &mut Window, delta: isize) {
-  buffer.apply_operations(
-    &[
-      vec![Op::MoveByLine(delta); window.height / 2],
-      vec![Op::Collapse],
-    ]
-    .concat(),
-  );
+fn move_by_window_page(buffer: &mut Buffer, window: &mut Window, pages: isize) {
+  let delta = pages.saturating_mul((window.height / 2) as isize);
+  buffer.apply_operations(&[Op::MoveByLine(delta), Op::Collapse]);
   center(buffer, window);
 }
 
@@ -212,26 +320,127 @@ pub fn paste(buffer: &mut Buffer, contents: &[String]) {
         .expect("should be able to retrieve selection at index less than length when adjusting selections after applying operation");
       next_selection.adjust(&buffer.contents, change.as_ref());
     }
-    change.map(|c| buffer.history.record(c));
+    if let Some(change) = change {
+      buffer.record(change);
+    }
   }
   buffer.history.commit();
   buffer.cleanup_overlaps();
 }
 
+fn primary_selection_regex(buffer: &Buffer) -> Option<Regex> {
+  if buffer.contents.len_chars() == 0 {
+    return None;
+  }
+  let content = buffer
+    .primary_selection()
+    .slice(&buffer.contents)
+    .to_string();
+  Regex::new(&escape(&content))
+}
+
+pub fn select_matching_brackets(buffer: &mut Buffer, extend: bool) -> Result<(), String> {
+  let mut found = false;
+  let selections = buffer
+    .selections
+    .iter()
+    .map(
+      |selection| match find_matching_bracket(&buffer.contents, selection.cursor()) {
+        Some(index) if extend => {
+          found = true;
+          selection.extend_to(index)
+        }
+        Some(index) => {
+          found = true;
+          Selection::new_at_end(index, index)
+        }
+        None => *selection,
+      },
+    )
+    .collect();
+  if !found {
+    return Err("no matching bracket found".into());
+  }
+  buffer.set_selections_and_primary(selections, buffer.primary_selection);
+  Ok(())
+}
+
+pub fn select_next_occurrence(buffer: &mut Buffer) -> Result<(), String> {
+  let Some(regex) = primary_selection_regex(buffer) else {
+    return Err("no occurrences found".into());
+  };
+  let contents_end = buffer.contents.len_chars().saturating_sub(1);
+  let last = buffer
+    .selections
+    .last()
+    .expect("selections should never be empty");
+  let is_selected = |(start, end): &(usize, usize)| {
+    buffer
+      .selections
+      .iter()
+      .any(|s| s.start() <= *start && *end <= s.end())
+  };
+  // look after the last selection first and wrap around to the start of the
+  // buffer if nothing unselected was found
+  let next = regex
+    .find(&buffer.contents, last.end().saturating_add(1), contents_end)
+    .find(|m| !is_selected(m))
+    .or_else(|| {
+      regex
+        .find(&buffer.contents, 0, contents_end)
+        .find(|m| !is_selected(m))
+    });
+  let Some((start, end)) = next else {
+    return Err("no other occurrences found".into());
+  };
+  let mut selections = buffer.selections.clone();
+  selections.push(Selection::new_at_end(start, end));
+  let primary_selection = selections.len() - 1;
+  buffer.set_selections_and_primary(selections, primary_selection);
+  Ok(())
+}
+
+pub fn select_all_occurrences(buffer: &mut Buffer) -> Result<(), String> {
+  let Some(regex) = primary_selection_regex(buffer) else {
+    return Err("no occurrences found".into());
+  };
+  let contents_end = buffer.contents.len_chars().saturating_sub(1);
+  let primary_start = buffer.primary_selection().start();
+  let selections = regex
+    .find(&buffer.contents, 0, contents_end)
+    .map(|(start, end)| Selection::new_at_end(start, end))
+    .collect::<Vec<_>>();
+  if selections.is_empty() {
+    return Err("no occurrences found".into());
+  }
+  let primary_selection = selections
+    .iter()
+    .position(|s| s.start() >= primary_start)
+    .unwrap_or(0);
+  buffer.set_selections_and_primary(selections, primary_selection);
+  Ok(())
+}
+
 pub fn undo(buffer: &mut Buffer) {
-  let Some(changes) = buffer.history.backward() else {
+  let Some(changes) = buffer.history.backward().cloned() else {
     return;
   };
-  let selections = changes.apply(&mut buffer.contents);
-  buffer.set_selections(selections);
+  buffer.apply_changes(&changes);
 }
 
 pub fn redo(buffer: &mut Buffer) {
-  let Some(changes) = buffer.history.forward() else {
+  let Some(changes) = buffer.history.forward().cloned() else {
     return;
   };
-  let selections = changes.apply(&mut buffer.contents);
-  buffer.set_selections(selections);
+  buffer.apply_changes(&changes);
+}
+
+fn register_keys(registry: &Registry, name: &str, count: usize) -> Option<Vec<Key>> {
+  let Some(Register::Content(contents)) = registry.get(name) else {
+    return None;
+  };
+  let keys = Key::from_input(contents.first()?);
+  Some((0..count).flat_map(|_| keys.iter().cloned()).collect())
 }
 
 pub fn take_register_target(registry: &mut Registry) -> Option<String> {
@@ -247,3 +456,79 @@ pub fn take_register_target(registry: &mut Registry) -> Option<String> {
     _ => None,
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn select_next_occurrence_wraps_around() {
+    let mut b = buffer("äb x äb x äb", selections(&[(5, 6)]));
+    select_next_occurrence(&mut b).unwrap();
+    assert_eq!(ranges(&b.selections), vec![(5, 6), (10, 11)]);
+    assert_eq!(b.primary_selection, 1);
+    select_next_occurrence(&mut b).unwrap();
+    assert_eq!(ranges(&b.selections), vec![(0, 1), (5, 6), (10, 11)]);
+    assert_eq!(b.primary_selection, 0);
+    assert!(select_next_occurrence(&mut b).is_err());
+  }
+
+  #[test]
+  fn select_next_occurrence_is_literal() {
+    let mut b = buffer("a.b axb a.b", selections(&[(0, 2)]));
+    select_next_occurrence(&mut b).unwrap();
+    assert_eq!(ranges(&b.selections), vec![(0, 2), (8, 10)]);
+  }
+
+  #[test]
+  fn select_all_occurrences_keeps_primary() {
+    let mut b = buffer("äb x äb x äb", selections(&[(5, 6)]));
+    select_all_occurrences(&mut b).unwrap();
+    assert_eq!(ranges(&b.selections), vec![(0, 1), (5, 6), (10, 11)]);
+    assert_eq!(b.primary_selection, 1);
+  }
+
+  fn press(b: &mut Buffer, registry: &mut Registry, input: &str) -> Vec<UpdateCommand> {
+    let mut mode = Normal::default();
+    let mut window = Window::new((80, 24));
+    let mut commands = vec![];
+    for key in Key::from_input(input) {
+      commands = mode.update(b, registry, &mut window, key);
+    }
+    assert!(mode.is_idle());
+    commands
+  }
+
+  #[test]
+  fn count_moves_stop_at_last_line() {
+    let mut registry = Registry::default();
+    let mut b = buffer("ab\ncd\nef", selections(&[(1, 1)]));
+    press(&mut b, &mut registry, "3j");
+    assert_eq!(ranges(&b.selections), vec![(7, 7)]);
+    press(&mut b, &mut registry, "j");
+    assert_eq!(ranges(&b.selections), vec![(7, 7)]);
+    press(&mut b, &mut registry, "10k");
+    assert_eq!(ranges(&b.selections), vec![(1, 1)]);
+  }
+
+  #[test]
+  fn count_extends_by_lines() {
+    let mut registry = Registry::default();
+    let mut b = buffer("ab\ncd\nef\ngh", selections(&[(0, 0)]));
+    press(&mut b, &mut registry, "2J");
+    assert_eq!(ranges(&b.selections), vec![(0, 6)]);
+  }
+
+  #[test]
+  fn count_repeats_playback() {
+    let mut registry = Registry::default();
+    registry.set("playback", Register::Content(vec!["a<RET>".into()]));
+    let mut b = buffer("", selections(&[(0, 0)]));
+    let commands = press(&mut b, &mut registry, "3 ");
+    let [UpdateCommand::SendKeys(keys)] = commands.as_slice() else {
+      panic!("playback should send keys");
+    };
+    assert_eq!(keys, &Key::from_input("a<RET>a<RET>a<RET>"));
+  }
+}
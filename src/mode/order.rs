@@ -0,0 +1,63 @@
+use crate::*;
+
+#[derive(Debug, Clone, Default)]
+pub struct Order {
+  editor: Option<MiniEditor>,
+}
+
+impl Order {
+  pub fn switch_to() -> UpdateCommand {
+    UpdateCommand::SwitchMode(Box::new(Self::default()))
+  }
+}
+
+impl Mode for Order {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    _registry: &mut Registry,
+    _window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    use crate::key::Key::*;
+    if let Some(editor) = &mut self.editor {
+      match editor.update(key) {
+        MiniEditorCommand::Cancel => return vec![Normal::switch_to()],
+        MiniEditorCommand::Submit => {
+          let pattern = editor.value.to_string();
+          let Some(regex) = Regex::new(&pattern) else {
+            return vec![UpdateCommand::Fail(format!(
+              "error: invalid pattern {pattern}"
+            ))];
+          };
+          sort_by_regex(buffer, &regex);
+          return vec![Normal::switch_to()];
+        }
+        MiniEditorCommand::Update => {}
+        MiniEditorCommand::None => {}
+      }
+      return vec![];
+    }
+    match key {
+      Esc => return vec![Normal::switch_to()],
+      Char('s') => sort_lexically(buffer),
+      Char('n') => sort_numerically(buffer),
+      Char('r') => {
+        self.editor = Some(MiniEditor::default());
+        return vec![];
+      }
+      Char('v') => reverse_selections(buffer),
+      Char('x') => shuffle_selections(buffer),
+      Char('u') => dedupe_selections(buffer),
+      _ => return vec![],
+    }
+    vec![Normal::switch_to()]
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    match &self.editor {
+      Some(editor) => format!("sort by > {}", editor.value).into(),
+      None => "order > [s]ort [n]umeric [r]egex re[v]erse shuffle[x] [u]nique".into(),
+    }
+  }
+}
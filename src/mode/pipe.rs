@@ -32,7 +32,7 @@ impl Mode for Pipe {
         );
         let mut results = match results {
           Ok(results) => results,
-          Err(error) => return vec![Normal::switch_to_with_toast(error)],
+          Err(error) => return vec![UpdateCommand::Fail(error)],
         };
         let mut selections = Vec::with_capacity(results.len());
         for i in 0..results.len() {
@@ -49,8 +49,12 @@ impl Mode for Pipe {
             next_selection.adjust(&buffer.contents, change_a.as_ref());
             next_selection.adjust(&buffer.contents, change_b.as_ref());
           }
-          change_a.map(|c| buffer.history.record(c));
-          change_b.map(|c| buffer.history.record(c));
+          if let Some(change) = change_a {
+            buffer.record(change);
+          }
+          if let Some(change) = change_b {
+            buffer.record(change);
+          }
         }
         buffer.history.commit();
         buffer.set_selections(selections);
@@ -0,0 +1,104 @@
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub struct Restore {
+  name: String,
+}
+
+impl Restore {
+  pub fn switch_to(name: String) -> UpdateCommand {
+    let mode = Self { name };
+    UpdateCommand::SwitchMode(Box::new(mode))
+  }
+}
+
+impl Mode for Restore {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    registry: &mut Registry,
+    window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    use crate::key::Key::*;
+    if key == Esc {
+      return vec![Normal::switch_to()];
+    }
+    let Some((saved, saved_primary)) = saved_selections(buffer, registry, &self.name) else {
+      return vec![UpdateCommand::Fail(format!(
+        "error: register {} holds no selections for this buffer",
+        self.name
+      ))];
+    };
+    let selections = match key {
+      Char('r') => saved,
+      Char('u') => union_selections(&buffer.selections, &saved),
+      Char('i') => intersect_selections(&buffer.selections, &saved),
+      Char('s') => subtract_selections(&buffer.selections, &saved),
+      _ => return vec![],
+    };
+    if selections.is_empty() {
+      return vec![UpdateCommand::Fail("no selections left".into())];
+    }
+    let jump = Jump::new(buffer, window);
+    let primary_selection = if key == Char('r') {
+      saved_primary.min(selections.len() - 1)
+    } else {
+      let cursor = buffer.primary_selection().cursor();
+      selections
+        .iter()
+        .position(|s| s.start() <= cursor && cursor <= s.end())
+        .unwrap_or(0)
+    };
+    buffer.set_selections_and_primary(selections, primary_selection);
+    vec![Normal::switch_to(), UpdateCommand::PushJump(jump)]
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    format!(
+      "restore {} > [r]eplace [u]nion [i]ntersect [s]ubtract",
+      self.name
+    )
+    .into()
+  }
+}
+
+pub fn saved_selections(
+  buffer: &Buffer,
+  registry: &Registry,
+  name: &str,
+) -> Option<(Vec<Selection>, usize)> {
+  match registry.get(name) {
+    Some(Register::Selections(owner, selections, primary)) if owner == &buffer.filename => {
+      // selections saved before the buffer shrank may reach past its end
+      let mut selections = selections.clone();
+      for selection in selections.iter_mut() {
+        selection.adjust(&buffer.contents, None);
+      }
+      Some((selections, *primary))
+    }
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn restored_selections_are_clamped() {
+    let mut b = buffer("abc", selections(&[(0, 0)]));
+    let mut registry = Registry::default();
+    let saved = Register::Selections(None, selections(&[(1, 2), (5, 9), (7, 8)]), 2);
+    registry.set("selections", saved);
+    let mut mode = Restore {
+      name: "selections".into(),
+    };
+    let mut window = Window::new((80, 24));
+    mode.update(&mut b, &mut registry, &mut window, Key::Char('r'));
+    assert_eq!(ranges(&b.selections), vec![(1, 2), (3, 3)]);
+    assert_eq!(b.primary_selection, 1);
+    assert_eq!(b.selection_contents(), vec!["bc", ""]);
+  }
+}
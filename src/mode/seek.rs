@@ -31,8 +31,8 @@ impl Mode for Seek {
   fn update(
     &mut self,
     buffer: &mut Buffer,
-    _registry: &mut Registry,
-    _window: &mut Window,
+    registry: &mut Registry,
+    window: &mut Window,
     key: Key,
   ) -> Vec<UpdateCommand> {
     match self.editor.update(key) {
@@ -44,11 +44,14 @@ impl Mode for Seek {
           true => reverse(&buffer.contents, &buffer.selections, &command),
           false => forward(&buffer.contents, &buffer.selections, &command),
         };
-        if let ModeResult::Ok(selections) = result {
-          buffer.primary_selection = selections.len().saturating_sub(1);
-          buffer.set_selections(selections);
-        }
-        return vec![Normal::switch_to()];
+        return match result {
+          ModeResult::Ok(selections) => {
+            apply_pattern_selections(buffer, registry, window, &command, self.reverse, selections)
+          }
+          ModeResult::Same => vec![UpdateCommand::Fail(format!("no matches for {command}"))],
+          ModeResult::Error => vec![UpdateCommand::Fail(format!("error: invalid pattern {command}"))],
+          ModeResult::Empty => vec![Normal::switch_to()],
+        };
       },
       MiniEditorCommand::None => { },
     }
@@ -75,6 +78,69 @@ impl Mode for Seek {
   }
 }
 
+pub const PATTERN_REGISTER: &str = "pattern";
+
+fn store_pattern(registry: &mut Registry, pattern: &str, reverse: bool) {
+  if pattern.is_empty() {
+    return;
+  }
+  let direction = if reverse { "backward" } else { "forward" };
+  let value = Register::Content(vec![pattern.to_string(), direction.to_string()]);
+  registry.set(PATTERN_REGISTER, value);
+}
+
+// moves to the selections a submitted pattern found and stores the pattern
+// to be repeated, only patterns that compiled and matched are worth that
+pub fn apply_pattern_selections(
+  buffer: &mut Buffer,
+  registry: &mut Registry,
+  window: &Window,
+  pattern: &str,
+  reverse: bool,
+  selections: Vec<Selection>,
+) -> Vec<UpdateCommand> {
+  store_pattern(registry, pattern, reverse);
+  let jump = Jump::new(buffer, window);
+  buffer.primary_selection = selections.len().saturating_sub(1);
+  buffer.set_selections(selections);
+  vec![Normal::switch_to(), UpdateCommand::PushJump(jump)]
+}
+
+pub fn repeat_seek(
+  buffer: &mut Buffer,
+  registry: &Registry,
+  opposite: bool,
+  count: usize,
+) -> Result<(), String> {
+  let Some(Register::Content(parts)) = registry.get(PATTERN_REGISTER) else {
+    return Err("no pattern to repeat".into());
+  };
+  let [pattern, direction, ..] = parts.as_slice() else {
+    return Err("no pattern to repeat".into());
+  };
+  let is_reverse = (direction == "backward") != opposite;
+  let mut seeked = false;
+  for _ in 0..count {
+    let result = match is_reverse {
+      true => reverse(&buffer.contents, &buffer.selections, pattern),
+      false => forward(&buffer.contents, &buffer.selections, pattern),
+    };
+    match result {
+      ModeResult::Ok(selections) => {
+        buffer.primary_selection = selections.len().saturating_sub(1);
+        buffer.set_selections(selections);
+        seeked = true;
+      }
+      ModeResult::Error => return Err(format!("error: invalid pattern {pattern}")),
+      ModeResult::Empty | ModeResult::Same => break,
+    }
+  }
+  if !seeked {
+    return Err(format!("no more matches for {pattern}"));
+  }
+  Ok(())
+}
+
 fn update_preview(mode: &mut Seek, buffer: &Buffer) {
   let command = mode.editor.value.to_string();
   let result = match mode.reverse {
@@ -127,7 +193,7 @@ fn reverse(contents: &Rope, selections: &[Selection], pattern: &str) -> ModeResu
   for selection in selections.iter() {
     // end the search a character before the active anchor to allow seeking to
     // the previous instance of the character under the cursor
-    let end = selection.cursor().saturating_sub(1).max(0);
+    let end = selection.cursor().saturating_sub(1);
     let result = regex.find(contents, 0, end).last();
     let new_selection = match result {
       Some((start, end)) => {
@@ -165,3 +231,34 @@ fn move_cursor(selection: Selection, start: usize, end: usize) -> Selection {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  fn submit(buffer: &mut Buffer, registry: &mut Registry, pattern: &str) {
+    let UpdateCommand::SwitchMode(mut mode) = Seek::switch_to(false) else {
+      panic!("seek should switch modes");
+    };
+    let mut window = Window::new((80, 24));
+    for key in Key::from_input(pattern).into_iter().chain([Key::Enter]) {
+      mode.update(buffer, registry, &mut window, key);
+    }
+  }
+
+  #[test]
+  fn stores_only_patterns_that_matched() {
+    let mut b = buffer("abc abc\n", selections(&[(0, 0)]));
+    let mut registry = Registry::default();
+    submit(&mut b, &mut registry, "(");
+    assert!(registry.get(PATTERN_REGISTER).is_none());
+    submit(&mut b, &mut registry, "x");
+    assert!(registry.get(PATTERN_REGISTER).is_none());
+    submit(&mut b, &mut registry, "b");
+    assert!(matches!(
+      registry.get(PATTERN_REGISTER),
+      Some(Register::Content(parts)) if parts == &["b", "forward"]
+    ));
+  }
+}
@@ -0,0 +1,96 @@
+use crate::*;
+
+#[derive(Debug, Clone)]
+enum State {
+  Action,
+  Pair { change: bool },
+  Tag { change: bool, editor: MiniEditor },
+}
+
+#[derive(Debug, Clone)]
+pub struct Surround {
+  state: State,
+}
+
+impl Surround {
+  pub fn switch_to() -> UpdateCommand {
+    let mode = Self {
+      state: State::Action,
+    };
+    UpdateCommand::SwitchMode(Box::new(mode))
+  }
+}
+
+impl Mode for Surround {
+  fn update(
+    &mut self,
+    buffer: &mut Buffer,
+    _registry: &mut Registry,
+    _window: &mut Window,
+    key: Key,
+  ) -> Vec<UpdateCommand> {
+    use crate::key::Key::*;
+    match &mut self.state {
+      State::Action => match key {
+        Esc => return vec![Normal::switch_to()],
+        Char('a') => self.state = State::Pair { change: false },
+        Char('c') => self.state = State::Pair { change: true },
+        Char('d') => {
+          return match delete_surround(buffer) {
+            Ok(()) => vec![Normal::switch_to()],
+            Err(e) => vec![UpdateCommand::Fail(e)],
+          };
+        }
+        _ => {}
+      },
+      State::Pair { change } => match key {
+        Esc => return vec![Normal::switch_to()],
+        Char('t') => {
+          self.state = State::Tag {
+            change: *change,
+            editor: Default::default(),
+          }
+        }
+        Char(ch) => {
+          if let Some((open, close)) = surround_pair(ch) {
+            return surround(buffer, *change, &open, &close);
+          }
+        }
+        _ => {}
+      },
+      State::Tag { change, editor } => match editor.update(key) {
+        MiniEditorCommand::Cancel => return vec![Normal::switch_to()],
+        MiniEditorCommand::Submit => {
+          let tag = editor.value.to_string();
+          return match tag_pair(&tag) {
+            Some((open, close)) => surround(buffer, *change, &open, &close),
+            None => vec![UpdateCommand::Fail("error: empty tag".into())],
+          };
+        }
+        MiniEditorCommand::Update => {}
+        MiniEditorCommand::None => {}
+      },
+    }
+    vec![]
+  }
+
+  fn status(&self) -> CowStr<'_> {
+    match &self.state {
+      State::Action => "surround > [a]dd [c]hange [d]elete".into(),
+      State::Pair { change: false } => "surround add > pair or [t]ag".into(),
+      State::Pair { change: true } => "surround change > pair or [t]ag".into(),
+      State::Tag { editor, .. } => format!("surround tag > {}", editor.value).into(),
+    }
+  }
+}
+
+fn surround(buffer: &mut Buffer, change: bool, open: &str, close: &str) -> Vec<UpdateCommand> {
+  if !change {
+    add_surround(buffer, open, close);
+    return vec![Normal::switch_to()];
+  }
+  match change_surround(buffer, open, close) {
+    Ok(()) => vec![Normal::switch_to()],
+    Err(e) => vec![UpdateCommand::Fail(e)],
+  }
+}
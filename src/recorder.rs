@@ -1,25 +1,223 @@
 use crate::*;
 use std::collections::VecDeque;
 
+const MAX_DEPTH: usize = 1000;
+const MAX_STEPS: usize = 1_000_000;
+
 #[derive(Default)]
 pub struct Recorder {
-  keys: Option<VecDeque<Key>>,
+  // pending keys with the playback depth that sent them, so that macros
+  // playing themselves can be stopped
+  keys: VecDeque<(Key, usize)>,
+  depth: usize,
+  played: usize,
+  command: Vec<Key>,
+  command_changed: bool,
+  recording: Option<(String, Vec<Key>)>,
+  // length of the recording when the current command started
+  command_start: usize,
 }
 
 impl Recorder {
-  pub fn take(&mut self) -> Option<impl Iterator<Item=Key>> {
-    let keys = self.keys.take()?;
-    Some(keys.into_iter())
+  pub fn next_key(&mut self) -> Option<Key> {
+    match self.keys.pop_front() {
+      Some((key, depth)) => {
+        self.played += 1;
+        self.depth = depth;
+        Some(key)
+      }
+      None => {
+        self.played = 0;
+        self.depth = 0;
+        None
+      }
+    }
+  }
+
+  // keys sent while playing back run before the ones still pending
+  pub fn add(&mut self, keys: Vec<Key>) -> Result<(), String> {
+    let depth = self.depth + 1;
+    if depth > MAX_DEPTH {
+      return Err(format!("playback nested deeper than {MAX_DEPTH} levels"));
+    }
+    if self.played + self.keys.len() + keys.len() > MAX_STEPS {
+      return Err(format!("playback exceeded {MAX_STEPS} keys"));
+    }
+    for key in keys.into_iter().rev() {
+      self.keys.push_front((key, depth));
+    }
+    Ok(())
+  }
+
+  pub fn is_playing(&self) -> bool {
+    self.played > 0
+  }
+
+  pub fn has_pending(&self) -> bool {
+    !self.keys.is_empty()
+  }
+
+  // drops the pending keys and returns how many were played, including the
+  // one that failed
+  pub fn abort(&mut self) -> usize {
+    self.keys.clear();
+    self.depth = 0;
+    std::mem::take(&mut self.played)
+  }
+
+  pub fn start_recording(&mut self, name: String) {
+    self.recording = Some((name, Vec::new()));
+  }
+
+  // the keys of the command that stopped the recording, counts and target
+  // register included, were already captured and are dropped
+  pub fn stop_recording(&mut self) -> Option<(String, Vec<Key>)> {
+    let (name, mut keys) = self.recording.take()?;
+    keys.truncate(self.command_start);
+    Some((name, keys))
+  }
+
+  pub fn recording(&self) -> Option<&str> {
+    self.recording.as_ref().map(|(name, _)| name.as_str())
+  }
+
+  pub fn capture(&mut self, key: Key) {
+    if let Some((_, keys)) = self.recording.as_mut() {
+      if self.command.is_empty() {
+        self.command_start = keys.len();
+      }
+      keys.push(key);
+    }
+  }
+
+  pub fn track(&mut self, key: Key, changed: bool) {
+    self.command.push(key);
+    self.command_changed |= changed;
+  }
+
+  // returns the keys of the command that just completed if it changed a
+  // buffer, so that it can be repeated
+  pub fn finish_command(&mut self) -> Option<Vec<Key>> {
+    let keys = std::mem::take(&mut self.command);
+    let changed = std::mem::replace(&mut self.command_changed, false);
+    changed.then_some(keys)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn nested_keys_run_first() {
+    let mut r = Recorder::default();
+    r.add(vec![Key::Char('a'), Key::Char('b')]).unwrap();
+    assert_eq!(r.next_key(), Some(Key::Char('a')));
+    r.add(vec![Key::Char('c')]).unwrap();
+    assert_eq!(r.next_key(), Some(Key::Char('c')));
+    assert_eq!(r.next_key(), Some(Key::Char('b')));
+    assert_eq!(r.next_key(), None);
+    assert!(!r.is_playing());
   }
 
-  pub fn add(&mut self, keys: Vec<Key>) {
-    if let Some(ref mut existing_keys) = self.keys {
-      for key in keys.iter().rev() {
-        existing_keys.push_front(*key);
+  #[test]
+  fn self_playing_keys_stop_at_max_depth() {
+    let mut r = Recorder::default();
+    let mut levels = 0;
+    while r.add(vec![Key::Char(' ')]).is_ok() {
+      levels += 1;
+      assert_eq!(r.next_key(), Some(Key::Char(' ')));
+    }
+    assert_eq!(levels, MAX_DEPTH);
+    assert_eq!(r.abort(), MAX_DEPTH);
+    assert_eq!(r.next_key(), None);
+  }
+
+  // runs keys the way the main loop does, each list being one command
+  fn type_commands(r: &mut Recorder, commands: &[(&[Key], bool)]) -> Vec<Option<Vec<Key>>> {
+    let mut repeats = vec![];
+    for &(keys, changed) in commands {
+      for (i, &key) in keys.iter().enumerate() {
+        r.capture(key);
+        r.track(key, changed && i == keys.len() - 1);
       }
-    } else {
-      self.keys = Some(keys.into());
+      repeats.push(r.finish_command());
     }
+    repeats
   }
 
+  #[test]
+  fn finished_commands_repeat_when_changed() {
+    use crate::key::Key::*;
+    let mut r = Recorder::default();
+    let repeats = type_commands(
+      &mut r,
+      &[
+        (&[Char('3'), Char('j')], false),
+        (&[Char('e'), Char('a'), Char('X')], true),
+      ],
+    );
+    assert_eq!(
+      repeats,
+      vec![None, Some(vec![Char('e'), Char('a'), Char('X')])]
+    );
+    assert_eq!(r.finish_command(), None);
+  }
+
+  #[test]
+  fn stopping_drops_the_whole_stop_command() {
+    use crate::key::Key::*;
+    let mut r = Recorder::default();
+    r.start_recording("playback".into());
+    type_commands(
+      &mut r,
+      &[(&[Char('2'), Char('j')], false), (&[Char('d')], true)],
+    );
+    assert_eq!(r.recording(), Some("playback"));
+    type_commands(&mut r, &[(&[Char('e'), Char('m'), Char('q')], false)]);
+    assert_eq!(
+      r.stop_recording(),
+      Some(("playback".into(), vec![Char('2'), Char('j'), Char('d')]))
+    );
+    assert_eq!(r.recording(), None);
+  }
+
+  #[test]
+  fn replayed_keys_are_not_recorded() {
+    use crate::key::Key::*;
+    let mut r = Recorder::default();
+    r.start_recording("playback".into());
+    r.capture(Char(' '));
+    r.track(Char(' '), false);
+    r.add(vec![Char('d')]).unwrap();
+    let key = r.next_key().unwrap();
+    r.track(key, true);
+    r.finish_command();
+    type_commands(&mut r, &[(&[Char('q')], false)]);
+    assert_eq!(
+      r.stop_recording(),
+      Some(("playback".into(), vec![Char(' ')]))
+    );
+  }
+
+  #[test]
+  fn tracked_keys_accumulate_until_finished() {
+    use crate::key::Key::*;
+    let mut r = Recorder::default();
+    // a target register and a count stay pending until the command runs
+    for (key, changed) in [(Char('e'), false), (Char('a'), false), (Char('2'), false)] {
+      r.track(key, changed);
+    }
+    r.track(Char('X'), true);
+    r.track(Esc, false);
+    assert_eq!(
+      r.finish_command(),
+      Some(vec![Char('e'), Char('a'), Char('2'), Char('X'), Esc])
+    );
+    r.track(Char('3'), false);
+    r.track(Char('j'), false);
+    assert_eq!(r.finish_command(), None);
+    r.track(Char('d'), true);
+    assert_eq!(r.finish_command(), Some(vec![Char('d')]));
+  }
 }
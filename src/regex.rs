@@ -26,13 +26,56 @@ impl Regex {
     range_start: usize,
     range_end: usize,
   ) -> impl Iterator<Item = (usize, usize)> + 'a {
+    // the regex engine searches over bytes while selections are tracked in
+    // chars so translate the range in and the matches back out
+    let len_chars = contents.len_chars();
+    let byte_start = contents.char_to_byte(range_start.min(len_chars));
+    let byte_end = contents.char_to_byte(range_end.saturating_add(1).min(len_chars));
     let mut input = Input::new(contents);
-    input.set_range(range_start..=range_end);
+    input.set_range(byte_start..byte_end);
     self.0.find_iter(input).map(|m| {
       let start = contents.byte_to_char(m.start());
-      let end = contents.byte_to_char(m.end().min(contents.len_chars()));
+      let end = contents.byte_to_char(m.end());
       let end = end.saturating_sub(1).max(start);
       (start, end)
     })
   }
 }
+
+pub fn escape(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+  for ch in value.chars() {
+    if "\\.+*?()|[]{}^$#&-~".contains(ch) {
+      escaped.push('\\');
+    }
+    escaped.push(ch);
+  }
+  escaped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn find(pattern: &str, text: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let contents = Rope::from_str(text);
+    let regex = Regex::new(pattern).expect("pattern should be valid");
+    regex.find(&contents, start, end).collect()
+  }
+
+  #[test]
+  fn matches_in_chars_after_multibyte_text() {
+    // "é" and "ö" take two bytes each, matches must still be char indices
+    assert_eq!(find("l+", "héllo wörld", 0, 10), vec![(2, 3), (9, 9)]);
+    assert_eq!(find("o", "héllo wörld", 5, 10), vec![]);
+    assert_eq!(find("ö", "héllo wörld", 5, 10), vec![(7, 7)]);
+    assert_eq!(find("l", "héllo wörld", 3, 8), vec![(3, 3)]);
+  }
+
+  #[test]
+  fn escaped_values_match_literally() {
+    assert_eq!(escape("a.b*(c)"), "a\\.b\\*\\(c\\)");
+    assert_eq!(escape("é-ö"), "é\\-ö");
+    assert_eq!(find(&escape("1+1=2?"), "11=2 1+1=2?", 0, 10), vec![(5, 10)]);
+  }
+}
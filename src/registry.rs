@@ -1,25 +1,288 @@
-use std::collections::HashMap;
+use crate::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+// registers holding more than this many bytes are not persisted
+const MAX_PERSISTED_SIZE: usize = 64 * 1024;
+// registers holding state of a single session, never persisted
+const TRANSIENT_REGISTERS: [&str; 4] = ["target", "repeat", CHAR_SEEK_REGISTER, PATTERN_REGISTER];
 
 pub enum Register {
   Content(Vec<String>),
-  //Selections(Vec<Selection>, usize),
+  Selections(Option<String>, Vec<Selection>, usize),
   //Regex(Regex),
   //Input(Rope),
 }
 
 #[derive(Default)]
-pub struct Registry(HashMap<String, Register>);
+pub struct Registry {
+  registers: HashMap<String, Register>,
+  // names set or deleted in this session, their state wins when saving
+  changed: HashSet<String>,
+  excluded: HashSet<String>,
+}
 
 impl Registry {
   pub fn set(&mut self, key: &str, value: Register) {
-    self.0.insert(key.to_string(), value);
+    self.registers.insert(key.to_string(), value);
+    self.changed.insert(key.to_string());
   }
 
   pub fn get(&self, key: &str) -> Option<&Register> {
-    self.0.get(key)
+    self.registers.get(key)
   }
 
   pub fn del(&mut self, key: &str) {
-    self.0.remove(key);
+    self.registers.remove(key);
+    self.changed.insert(key.to_string());
+  }
+
+  // registers with these names are not saved, in addition to the transient ones
+  pub fn exclude_from_saving<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+    self.excluded.extend(
+      names
+        .into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from),
+    );
+  }
+
+  // registers stored in the file are added unless one with the same name is
+  // already set
+  pub fn load(&mut self, path: &Path) -> io::Result<()> {
+    for (name, values) in read_registers(path)? {
+      self
+        .registers
+        .entry(name)
+        .or_insert(Register::Content(values));
+    }
+    Ok(())
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    // other instances save between our read and our rename otherwise, and
+    // their registers would be lost
+    let _lock = lock(&path.with_extension("lock"))?;
+    // keep the registers other instances saved in the meantime, unless this
+    // session set or deleted them; a file that cannot be read is replaced
+    let mut registers = read_registers(path)
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|(name, _)| !self.changed.contains(name))
+      .collect::<BTreeMap<_, _>>();
+    for name in &self.changed {
+      if let Some(Register::Content(values)) = self.registers.get(name) {
+        registers.insert(name.clone(), values.clone());
+      }
+    }
+    registers.retain(|name, values| self.is_persisted(name, values));
+    let mut output = String::new();
+    for (name, values) in registers {
+      let fields = std::iter::once(&name)
+        .chain(&values)
+        .map(|field| escape_field(field))
+        .collect::<Vec<_>>();
+      output.push_str(&fields.join("\t"));
+      output.push('\n');
+    }
+    // written next to the target and renamed over it so that readers never
+    // see a partially written file
+    let temp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+    fs::write(&temp_path, output)?;
+    fs::rename(&temp_path, path)
+  }
+
+  fn is_persisted(&self, name: &str, values: &[String]) -> bool {
+    let size = values.iter().map(|value| value.len()).sum::<usize>();
+    !TRANSIENT_REGISTERS.contains(&name)
+      && !self.excluded.contains(name)
+      && size <= MAX_PERSISTED_SIZE
+  }
+
+  pub fn adjust(&mut self, filename: Option<&str>, len_chars: usize, changes: &[Change]) {
+    if changes.is_empty() {
+      return;
+    }
+    for register in self.registers.values_mut() {
+      if let Register::Selections(owner, selections, _) = register {
+        if owner.as_deref() == filename {
+          adjust_selections(selections, len_chars, changes);
+        }
+      }
+    }
+  }
+}
+
+pub fn registers_state_path() -> Option<PathBuf> {
+  let state_home = std::env::var_os("XDG_STATE_HOME")
+    .map(PathBuf::from)
+    .filter(|path| path.is_absolute())
+    .or_else(|| {
+      let home = std::env::var_os("HOME")?;
+      Some(PathBuf::from(home).join(".local").join("state"))
+    })?;
+  Some(state_home.join("rust-editor").join("registers"))
+}
+
+// invalid UTF-8 is replaced rather than failing the whole file
+fn read_registers(path: &Path) -> io::Result<Vec<(String, Vec<String>)>> {
+  let contents = match fs::read(path) {
+    Ok(contents) => contents,
+    Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+    Err(e) => return Err(e),
+  };
+  let registers = String::from_utf8_lossy(&contents)
+    .lines()
+    .filter_map(|line| {
+      let mut fields = line.split('\t').map(unescape);
+      let name = fields.next().filter(|name| !name.is_empty())?;
+      Some((name, fields.collect()))
+    })
+    .collect();
+  Ok(registers)
+}
+
+// held until the returned file is dropped
+fn lock(path: &Path) -> io::Result<File> {
+  let file = fs::OpenOptions::new()
+    .create(true)
+    .truncate(false)
+    .write(true)
+    .open(path)?;
+  file.lock()?;
+  Ok(file)
+}
+
+fn escape_field(field: &str) -> String {
+  let mut escaped = String::with_capacity(field.len());
+  for ch in field.chars() {
+    match ch {
+      '\\' => escaped.push_str("\\\\"),
+      '\t' => escaped.push_str("\\t"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      ch => escaped.push(ch),
+    }
+  }
+  escaped
+}
+
+fn unescape(field: &str) -> String {
+  let mut unescaped = String::with_capacity(field.len());
+  let mut chars = field.chars();
+  while let Some(ch) = chars.next() {
+    if ch != '\\' {
+      unescaped.push(ch);
+      continue;
+    }
+    match chars.next() {
+      Some('t') => unescaped.push('\t'),
+      Some('n') => unescaped.push('\n'),
+      Some('r') => unescaped.push('\r'),
+      Some(ch) => unescaped.push(ch),
+      None => unescaped.push('\\'),
+    }
+  }
+  unescaped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn escaping_round_trip() {
+    let field = "a\tb\nc\\d\\n";
+    assert_eq!(escape_field(field), "a\\tb\\nc\\\\d\\\\n");
+    assert_eq!(unescape(&escape_field(field)), field);
+  }
+
+  fn temp_path(test: &str) -> PathBuf {
+    std::env::temp_dir()
+      .join(format!("rust-editor-test-{}-{test}", std::process::id()))
+      .join("registers")
+  }
+
+  fn content(registry: &Registry, name: &str) -> Option<Vec<String>> {
+    match registry.get(name) {
+      Some(Register::Content(values)) => Some(values.clone()),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn save_and_load() {
+    let path = temp_path("save");
+    let mut registry = Registry::default();
+    registry.exclude_from_saving("secret, ".split(','));
+    registry.set(
+      "playback",
+      Register::Content(vec!["a\tb<ESC>".into(), "".into()]),
+    );
+    registry.set(
+      "clipboard",
+      Register::Content(vec!["x".repeat(MAX_PERSISTED_SIZE + 1)]),
+    );
+    registry.set("selections", Register::Selections(None, vec![], 0));
+    for name in TRANSIENT_REGISTERS.into_iter().chain(["secret"]) {
+      registry.set(name, Register::Content(vec!["x".into()]));
+    }
+    registry.save(&path).unwrap();
+    let mut loaded = Registry::default();
+    loaded.load(&path).unwrap();
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert_eq!(
+      content(&loaded, "playback"),
+      Some(vec!["a\tb<ESC>".into(), "".into()])
+    );
+    assert_eq!(loaded.registers.len(), 1);
+  }
+
+  #[test]
+  fn session_changes_win_over_saved_ones() {
+    let path = temp_path("merge");
+    let mut first = Registry::default();
+    first.set("a", Register::Content(vec!["first".into()]));
+    first.set("b", Register::Content(vec!["first".into()]));
+    first.save(&path).unwrap();
+    let mut second = Registry::default();
+    second.load(&path).unwrap();
+    let mut other = Registry::default();
+    other.set("a", Register::Content(vec!["other".into()]));
+    other.set("c", Register::Content(vec!["other".into()]));
+    other.save(&path).unwrap();
+    // "a" was only loaded, so the newer value on disk is kept
+    second.del("b");
+    second.set("d", Register::Content(vec!["second".into()]));
+    second.save(&path).unwrap();
+    let mut loaded = Registry::default();
+    loaded.load(&path).unwrap();
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert_eq!(content(&loaded, "a"), Some(vec!["other".into()]));
+    assert_eq!(content(&loaded, "b"), None);
+    assert_eq!(content(&loaded, "c"), Some(vec!["other".into()]));
+    assert_eq!(content(&loaded, "d"), Some(vec!["second".into()]));
+  }
+
+  #[test]
+  fn invalid_file_is_replaced() {
+    let path = temp_path("invalid");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, b"a\tok\nb\t\xff\n").unwrap();
+    let mut registry = Registry::default();
+    registry.load(&path).unwrap();
+    assert_eq!(content(&registry, "a"), Some(vec!["ok".into()]));
+    assert_eq!(content(&registry, "b"), Some(vec!["\u{fffd}".into()]));
+    registry.set("c", Register::Content(vec!["new".into()]));
+    registry.save(&path).unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert!(saved.contains("c\tnew\n"));
   }
 }
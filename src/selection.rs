@@ -88,6 +88,68 @@ impl Selection {
     contents.slice(range)
   }
 
+  pub fn extend_to(&self, index: usize) -> Selection {
+    // move the active anchor to the provided index while keeping the passive
+    // anchor in place, swapping sides if the active anchor crosses over
+    match self.side {
+      Side::Start => {
+        if index > self.end {
+          Selection::new_at_end(self.end, index)
+        } else {
+          Selection::new_at_start(index, self.end)
+        }
+      }
+      Side::End => {
+        if index < self.start {
+          Selection::new_at_start(index, self.start)
+        } else {
+          Selection::new_at_end(self.start, index)
+        }
+      }
+    }
+  }
+
+  pub fn copy_by_line(&self, contents: &Rope, delta: isize) -> Option<Selection> {
+    // place a copy on the nth line in the direction of delta where both
+    // anchors fit, skipping over lines that are too short
+    let start_line = contents.char_to_line(self.start.min(contents.len_chars()));
+    let end_line = contents.char_to_line(self.end.min(contents.len_chars()));
+    let (start_offset, end_offset) = match self.last_line_offset {
+      Some(last_line_offset) if self.start == self.end => (last_line_offset, last_line_offset),
+      _ => (
+        self.start - contents.line_to_char(start_line),
+        self.end - contents.line_to_char(end_line),
+      ),
+    };
+    let mut remaining = delta.unsigned_abs();
+    let mut shift = 0;
+    while remaining > 0 {
+      shift += 1;
+      let (new_start_line, new_end_line) = if delta > 0 {
+        (start_line + shift, end_line + shift)
+      } else {
+        (start_line.checked_sub(shift)?, end_line.checked_sub(shift)?)
+      };
+      if new_end_line >= contents.len_lines() {
+        return None;
+      }
+      let start = line_offset_to_char(contents, new_start_line, start_offset);
+      let end = line_offset_to_char(contents, new_end_line, end_offset);
+      if let (Some(start), Some(end)) = (start, end) {
+        remaining -= 1;
+        if remaining == 0 {
+          return Some(Selection {
+            start,
+            end,
+            side: self.side,
+            last_line_offset: self.last_line_offset,
+          });
+        }
+      }
+    }
+    None
+  }
+
   pub fn try_merge(&self, other: &Self) -> Option<Selection> {
     if other.start < self.start {
       other.try_merge(self)
@@ -114,7 +176,10 @@ impl Selection {
   }
 
   pub fn adjust(&mut self, contents: &Rope, change: Option<&Change>) {
-    let max = contents.len_chars();
+    self.adjust_within(contents.len_chars(), change);
+  }
+
+  pub fn adjust_within(&mut self, max: usize, change: Option<&Change>) {
     match change {
       None => {
         self.start = self.start.min(max);
@@ -194,11 +259,12 @@ impl Selection {
   }
 
   fn move_by_line(&mut self, contents: &Rope, delta: isize) -> Option<Change> {
-    let max = contents.len_lines();
+    // moves past the first or last line stop there
+    let max = contents.len_lines().saturating_sub(1);
     let cursor = self.cursor();
     let line = self.cursor_line(contents);
     let new_line = step(max, line, delta);
-    if new_line >= contents.len_lines() {
+    if new_line == line {
       return None;
     }
     let line_begin = contents.line_to_char(line);
@@ -281,6 +347,35 @@ fn step(max: usize, value: usize, delta: isize) -> usize {
   }
 }
 
+fn line_offset_to_char(contents: &Rope, line: usize, offset: usize) -> Option<usize> {
+  let line_len = contents.line(line).len_chars();
+  let is_last_line = line + 1 == contents.len_lines();
+  if offset < line_len || (is_last_line && offset == line_len) {
+    Some(contents.line_to_char(line) + offset)
+  } else {
+    None
+  }
+}
+
+pub fn adjust_selections(selections: &mut [Selection], len_chars: usize, changes: &[Change]) {
+  // walk back from the final length to find how long the contents were after
+  // each change so that clamping matches what the buffer saw at the time
+  let mut lens = Vec::with_capacity(changes.len());
+  let mut len = len_chars;
+  for change in changes.iter().rev() {
+    lens.push(len);
+    len = match change {
+      Change::Addition(_, content) => len.saturating_sub(content.len_chars()),
+      Change::Removal(_, content) => len.saturating_add(content.len_chars()),
+    };
+  }
+  for (change, len) in changes.iter().zip(lens.into_iter().rev()) {
+    for selection in selections.iter_mut() {
+      selection.adjust_within(len, Some(change));
+    }
+  }
+}
+
 pub fn merge_overlapping_selections(selections: &mut Vec<Selection>) {
   selections.sort_by(|a, b| {
     a.start()
@@ -307,3 +402,136 @@ pub fn merge_overlapping_selections(selections: &mut Vec<Selection>) {
     selections
   };
 }
+
+pub fn union_selections(a: &[Selection], b: &[Selection]) -> Vec<Selection> {
+  let mut selections = [a, b].concat();
+  merge_overlapping_selections(&mut selections);
+  selections
+}
+
+pub fn intersect_selections(a: &[Selection], b: &[Selection]) -> Vec<Selection> {
+  let mut selections = vec![];
+  for x in a.iter() {
+    for y in b.iter() {
+      let start = x.start().max(y.start());
+      let end = x.end().min(y.end());
+      if start <= end {
+        selections.push(match x.side() {
+          Side::Start => Selection::new_at_start(start, end),
+          Side::End => Selection::new_at_end(start, end),
+        });
+      }
+    }
+  }
+  merge_overlapping_selections(&mut selections);
+  selections
+}
+
+pub fn subtract_selections(a: &[Selection], b: &[Selection]) -> Vec<Selection> {
+  let mut b = b.to_vec();
+  merge_overlapping_selections(&mut b);
+  let mut selections = vec![];
+  for x in a.iter() {
+    // carve every overlapping range out of the selection from left to right
+    let mut next_start = Some(x.start());
+    let mut overlapped = false;
+    for y in b.iter() {
+      let Some(start) = next_start else {
+        break;
+      };
+      if y.end() < start || y.start() > x.end() {
+        continue;
+      }
+      overlapped = true;
+      if y.start() > start {
+        selections.push(Selection::new_at_end(start, y.start() - 1));
+      }
+      next_start = y.end().checked_add(1).filter(|&s| s <= x.end());
+    }
+    if !overlapped {
+      selections.push(*x);
+    } else if let Some(start) = next_start {
+      selections.push(Selection::new_at_end(start, x.end()));
+    }
+  }
+  merge_overlapping_selections(&mut selections);
+  selections
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  fn union(a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    ranges(&union_selections(&selections(a), &selections(b)))
+  }
+
+  fn intersect(a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    ranges(&intersect_selections(&selections(a), &selections(b)))
+  }
+
+  fn subtract(a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    ranges(&subtract_selections(&selections(a), &selections(b)))
+  }
+
+  #[test]
+  fn union_of_selections() {
+    assert_eq!(union(&[(0, 9)], &[(2, 3)]), vec![(0, 9)]);
+    assert_eq!(union(&[(0, 2)], &[(3, 5)]), vec![(0, 2), (3, 5)]);
+    assert_eq!(union(&[(2, 6)], &[(0, 3), (5, 9)]), vec![(0, 9)]);
+  }
+
+  #[test]
+  fn intersection_of_selections() {
+    assert_eq!(
+      intersect(&[(0, 9)], &[(2, 3), (5, 6)]),
+      vec![(2, 3), (5, 6)]
+    );
+    assert_eq!(intersect(&[(2, 3)], &[(0, 9)]), vec![(2, 3)]);
+    assert_eq!(intersect(&[(0, 2)], &[(3, 5)]), vec![]);
+    assert_eq!(
+      intersect(&[(2, 6)], &[(0, 3), (5, 9)]),
+      vec![(2, 3), (5, 6)]
+    );
+  }
+
+  #[test]
+  fn subtraction_of_selections() {
+    assert_eq!(subtract(&[(2, 3)], &[(0, 9)]), vec![]);
+    assert_eq!(subtract(&[(2, 3)], &[(2, 3)]), vec![]);
+    assert_eq!(subtract(&[(0, 2)], &[(3, 5)]), vec![(0, 2)]);
+    assert_eq!(subtract(&[(3, 5)], &[(0, 2), (6, 7)]), vec![(3, 5)]);
+    assert_eq!(subtract(&[(2, 8)], &[(0, 3), (7, 9)]), vec![(4, 6)]);
+    assert_eq!(subtract(&[(0, 9)], &[(3, 4)]), vec![(0, 2), (5, 9)]);
+    assert_eq!(subtract(&[(0, 5)], &[(0, 0), (5, 5)]), vec![(1, 4)]);
+  }
+
+  #[test]
+  fn copy_by_line_skips_short_lines() {
+    let contents = Rope::from_str("abc\nd\nefg\n");
+    let selection = Selection::new_at_end(1, 2);
+    let copy = |selection: &Selection, delta| {
+      let copy = selection.copy_by_line(&contents, delta)?;
+      Some((copy.start(), copy.end()))
+    };
+    assert_eq!(copy(&selection, 1), Some((7, 8)));
+    assert_eq!(copy(&selection, 2), None);
+    assert_eq!(copy(&selection, -1), None);
+    let below = Selection::new_at_end(7, 8);
+    assert_eq!(copy(&below, -1), Some((1, 2)));
+    assert_eq!(copy(&below, 1), None);
+  }
+
+  #[test]
+  fn copy_by_line_keeps_multiline_shape() {
+    let contents = Rope::from_str("ab\ncd\nef\ngh");
+    let selection = Selection::new_at_start(1, 4);
+    let copy = selection
+      .copy_by_line(&contents, 2)
+      .expect("copy should fit two lines down");
+    assert_eq!((copy.start(), copy.end()), (7, 10));
+    assert_eq!(copy.side(), Side::Start);
+    assert!(selection.copy_by_line(&contents, 3).is_none());
+  }
+}
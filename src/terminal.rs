@@ -1,12 +1,13 @@
 use crate::*;
 use crossterm::event::{
-  read, DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, KeyCode, KeyModifiers, MouseEventKind,
+  poll, read, DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, KeyCode, KeyModifiers, MouseEventKind,
 };
 use crossterm::style::{Print, ResetColor, SetBackgroundColor, SetForegroundColor};
 use crossterm::terminal::ClearType;
 use crossterm::{cursor, execute, queue, terminal};
 use gag::Hold;
 use std::io::{self, BufWriter, Stdout, Write};
+use std::time::Duration;
 
 impl From<Color> for crossterm::style::Color {
   fn from(c: Color) -> Self {
@@ -92,38 +93,55 @@ impl Terminal {
   pub fn poll(&mut self) -> Event {
     loop {
       let event = read().expect("should read input");
-      match event {
-        CrosstermEvent::Mouse(event) => match event.kind {
-          MouseEventKind::ScrollUp => return Event::Key(Key::Up),
-          MouseEventKind::ScrollDown => return Event::Key(Key::Down),
-          MouseEventKind::ScrollLeft => return Event::Key(Key::Left),
-          MouseEventKind::ScrollRight => return Event::Key(Key::Right),
-          _ => {}
-        },
-        CrosstermEvent::Key(event) => match event.code {
-          KeyCode::Backspace => return Event::Key(Key::Backspace),
-          KeyCode::Enter => return Event::Key(Key::Enter),
-          KeyCode::Left => return Event::Key(Key::Left),
-          KeyCode::Right => return Event::Key(Key::Right),
-          KeyCode::Up => return Event::Key(Key::Up),
-          KeyCode::Down => return Event::Key(Key::Down),
-          KeyCode::Tab => return Event::Key(Key::Tab),
-          KeyCode::Esc => return Event::Key(Key::Esc),
-          KeyCode::Char('z') if event.modifiers & KeyModifiers::CONTROL == KeyModifiers::CONTROL => {
-            self.suspend();
-            return Event::Redraw;
-          },
-          KeyCode::Char(c) => return Event::Key(Key::Char(c)),
-          _ => {}
+      if let Some(event) = self.convert(event) {
+        return event;
+      }
+    }
+  }
+
+  // returns an event only if one is already waiting, without blocking
+  pub fn poll_pending(&mut self) -> Option<Event> {
+    while poll(Duration::ZERO).expect("should poll input") {
+      let event = read().expect("should read input");
+      if let Some(event) = self.convert(event) {
+        return Some(event);
+      }
+    }
+    None
+  }
+
+  fn convert(&mut self, event: CrosstermEvent) -> Option<Event> {
+    match event {
+      CrosstermEvent::Mouse(event) => match event.kind {
+        MouseEventKind::ScrollUp => Some(Event::Key(Key::Up)),
+        MouseEventKind::ScrollDown => Some(Event::Key(Key::Down)),
+        MouseEventKind::ScrollLeft => Some(Event::Key(Key::Left)),
+        MouseEventKind::ScrollRight => Some(Event::Key(Key::Right)),
+        _ => None,
+      },
+      CrosstermEvent::Key(event) => match event.code {
+        KeyCode::Backspace => Some(Event::Key(Key::Backspace)),
+        KeyCode::Enter => Some(Event::Key(Key::Enter)),
+        KeyCode::Left => Some(Event::Key(Key::Left)),
+        KeyCode::Right => Some(Event::Key(Key::Right)),
+        KeyCode::Up => Some(Event::Key(Key::Up)),
+        KeyCode::Down => Some(Event::Key(Key::Down)),
+        KeyCode::Tab => Some(Event::Key(Key::Tab)),
+        KeyCode::Esc => Some(Event::Key(Key::Esc)),
+        KeyCode::Char('z') if event.modifiers & KeyModifiers::CONTROL == KeyModifiers::CONTROL => {
+          self.suspend();
+          Some(Event::Redraw)
         },
-        CrosstermEvent::Resize(width, height) => {
-          self.width = width as usize;
-          self.height = height as usize;
-          self.buffer.resize(self.width * self.height, Cell::Unknown);
-          return Event::Redraw;
-        }
-        _ => {}
+        KeyCode::Char(c) => Some(Event::Key(Key::Char(c))),
+        _ => None,
+      },
+      CrosstermEvent::Resize(width, height) => {
+        self.width = width as usize;
+        self.height = height as usize;
+        self.buffer.resize(self.width * self.height, Cell::Unknown);
+        Some(Event::Redraw)
       }
+      _ => None,
     }
   }
 
@@ -0,0 +1,25 @@
+use crate::*;
+use ropey::Rope;
+
+// a scratch buffer holding text with the given selections
+pub fn buffer(text: &str, selections: Vec<Selection>) -> Buffer {
+  let mut buffer = Buffer::new_scratch();
+  buffer.contents = Rope::from_str(text);
+  buffer.set_selections(selections);
+  buffer
+}
+
+// forward selections over inclusive ranges
+pub fn selections(ranges: &[(usize, usize)]) -> Vec<Selection> {
+  ranges
+    .iter()
+    .map(|&(start, end)| Selection::new_at_end(start, end))
+    .collect()
+}
+
+pub fn ranges(selections: &[Selection]) -> Vec<(usize, usize)> {
+  selections
+    .iter()
+    .map(|selection| (selection.start(), selection.end()))
+    .collect()
+}
@@ -8,6 +8,7 @@ pub struct Theme {
   pub selection_secondary_face: (Option<Color>, Option<Color>),
   pub cursor_primary_face: (Option<Color>, Option<Color>),
   pub cursor_secondary_face: (Option<Color>, Option<Color>),
+  pub matching_bracket_face: (Option<Color>, Option<Color>),
   pub status_face: (Option<Color>, Option<Color>),
 }
 
@@ -33,11 +34,16 @@ impl Ui {
     self.terminal.poll()
   }
 
-  pub fn render(&mut self, view: &View) {
+  pub fn poll_pending(&mut self) -> Option<Event> {
+    self.terminal.poll_pending()
+  }
+
+  pub fn render(&mut self, view: &View, recording: Option<&str>) {
     let View {
       buffer,
       window,
       mode,
+      ..
     } = view;
     self.terminal.clear();
     let (width, height) = self.terminal.size();
@@ -45,6 +51,10 @@ impl Ui {
       Some(selections) => (selections, None),
       None => (&buffer.selections, Some(buffer.primary_selection())),
     };
+    // only brackets on screen get highlighted, so the search stops at its edges
+    let visible_lines = window.scroll_top..window.scroll_top + height;
+    let matching_bracket = primary_selection
+      .and_then(|s| find_matching_bracket_within(&buffer.contents, s.cursor(), visible_lines));
     // render buffer contents
     {
       let mut selection_iter = selections.iter();
@@ -81,7 +91,11 @@ impl Ui {
           }
           let (is_selection, is_primary, is_cursor) =
             Self::properties(index, current_selection, primary_selection);
-          let (bg, fg) = self.style(is_selection, is_primary, is_cursor);
+          let (bg, fg) = if !is_selection && matching_bracket == Some(index) {
+            self.theme.matching_bracket_face
+          } else {
+            self.style(is_selection, is_primary, is_cursor)
+          };
           let ch = match ch {
             '\n' => {
               if !is_selection {
@@ -115,7 +129,10 @@ impl Ui {
     }
     // render status bar
     {
-      let status_left = mode.status();
+      let status_left = match recording {
+        Some(name) => format!("[recording {name}] {}", mode.status()).into(),
+        None => mode.status(),
+      };
       let status_left_size = status_left.chars().count();
       let status_right = {
         let cursor_location = match primary_selection {
@@ -4,6 +4,7 @@ pub struct View {
   pub buffer: Buffer,
   pub window: Window,
   pub mode: Box<dyn Mode>,
+  pub jumps: Jumplist,
 }
 
 #[derive(Default)]
@@ -27,6 +28,7 @@ impl Views {
       buffer,
       window,
       mode: Box::new(Normal::default()),
+      jumps: Jumplist::default(),
     };
     self.entries.push(view);
     index